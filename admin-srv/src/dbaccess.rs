@@ -4,7 +4,9 @@ use entity::entity::admin::Column;
 use sea_orm::prelude::Expr;
 use sea_orm::ActiveValue::Set;
 use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter};
+use tracing::instrument;
 
+#[instrument(skip_all)]
 pub async fn count_admin_by_email(db: &DatabaseConnection, email: &str) -> Result<i32> {
     let row_affected = admin::Entity::find()
         .filter(Column::Email.eq(email))
@@ -13,11 +15,13 @@ pub async fn count_admin_by_email(db: &DatabaseConnection, email: &str) -> Resul
     Ok(row_affected as i32)
 }
 
+#[instrument(skip_all)]
 pub async fn count_admin_by_id(db: &DatabaseConnection, id: i32) -> Result<i32> {
     let row_affected = admin::Entity::find_by_id(id).count(db).await?;
     Ok(row_affected as i32)
 }
 
+#[instrument(skip_all)]
 pub async fn select_admin_by_email(
     db: &DatabaseConnection,
     email: &str,
@@ -29,6 +33,7 @@ pub async fn select_admin_by_email(
     Ok(res)
 }
 
+#[instrument(skip_all)]
 pub async fn select_admin_by_id(
     db: &DatabaseConnection,
     id: i32,
@@ -41,6 +46,7 @@ pub async fn select_admin_by_id(
     Ok(select.one(db).await?)
 }
 
+#[instrument(skip_all)]
 pub async fn update_admin_pwd(db: &DatabaseConnection, id: i32, new_pwd: &str) -> Result<i32> {
     let res = admin::Entity::update_many()
         .filter(Column::Id.eq(id))
@@ -50,6 +56,7 @@ pub async fn update_admin_pwd(db: &DatabaseConnection, id: i32, new_pwd: &str) -
     Ok(res.rows_affected as i32)
 }
 
+#[instrument(skip_all)]
 pub async fn select_admins(
     db: &DatabaseConnection,
     email: &Option<String>,
@@ -65,6 +72,7 @@ pub async fn select_admins(
     Ok(select.all(db).await?)
 }
 
+#[instrument(skip_all)]
 pub async fn insert_admin(db: &DatabaseConnection, email: &str, password: &str) -> Result<i32> {
     let new_admin = admin::ActiveModel {
         email: Set(email.to_owned()),
@@ -75,6 +83,7 @@ pub async fn insert_admin(db: &DatabaseConnection, email: &str, password: &str)
     Ok(res.last_insert_id)
 }
 
+#[instrument(skip_all)]
 pub async fn update_admin_del(db: &DatabaseConnection, id: i32) -> Result<bool> {
     let res = admin::Entity::update_many()
         .filter(Column::Id.eq(id))
@@ -1,16 +1,20 @@
 use proto::admin_service_server::AdminServiceServer;
-use util::{get_db_connection, get_service_addr};
+use util::health::health_service;
+use util::interceptor::CheckServiceToken;
+use util::metrics::serve_metrics;
+use util::{
+    apply_pending_migrations, get_db_connection, get_service_addr, grpc_server, shutdown_signal,
+};
 
 mod dbaccess;
 mod server;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    tracing_subscriber::fmt()
-        .with_target(false)
-        .compact()
-        .init();
-
+    let config = util::telemetry::init(util::Service::Admin).map_err(|err| {
+        tracing::error!("{}", err);
+        err
+    })?;
     let addr = get_service_addr(util::Service::Admin).map_err(|err| {
         tracing::error!("{}", err);
         err
@@ -19,18 +23,41 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         tracing::error!("{}", err);
         err
     })?;
+    apply_pending_migrations(&db).await.map_err(|err| {
+        tracing::error!("{}", err);
+        err
+    })?;
+    let auth = CheckServiceToken::from_config().map_err(|err| {
+        tracing::error!("{}", err);
+        err
+    })?;
 
+    let health = health_service::<AdminServiceServer<server::Admin>>(
+        db.clone(),
+        config.database.health_check_interval(),
+    );
     let admin_service = server::Admin::new(db);
 
+    serve_metrics(util::Service::Admin).await.map_err(|err| {
+        tracing::error!("{}", err);
+        err
+    })?;
+    let mut grpc = grpc_server(util::Service::Admin).map_err(|err| {
+        tracing::error!("{}", err);
+        err
+    })?;
+
     tracing::info!("Admin Service runs at: {}", addr);
-    tonic::transport::Server::builder()
-        .add_service(AdminServiceServer::new(admin_service))
-        .serve(addr.parse()?)
+    grpc
+        .add_service(health)
+        .add_service(AdminServiceServer::with_interceptor(admin_service, auth))
+        .serve_with_shutdown(addr.parse()?, shutdown_signal())
         .await
         .map_err(|err| {
             tracing::error!("{}", err);
             err
         })?;
 
+    util::telemetry::shutdown().await;
     Ok(())
 }
@@ -5,12 +5,11 @@ use proto::{
     ToggleAdminRequest,
 };
 use tonic::Request;
-use util::get_service_url;
+use util::interceptor::connect;
 
 #[tokio::test]
 async fn test_create_admin() {
-    let client_url = get_service_url(util::Service::Admin).unwrap();
-    let mut client = AdminServiceClient::connect(client_url).await.unwrap();
+    let mut client = AdminServiceClient::new(connect(util::Service::Admin).unwrap());
     let request = Request::new(CreateAdminRequest {
         email: "cakeal@qq.com".to_string(),
         password: "12345678".to_string(),
@@ -21,8 +20,7 @@ async fn test_create_admin() {
 
 #[tokio::test]
 async fn test_list_admin() {
-    let client_url = get_service_url(util::Service::Admin).unwrap();
-    let mut client = AdminServiceClient::connect(client_url).await.unwrap();
+    let mut client = AdminServiceClient::new(connect(util::Service::Admin).unwrap());
     let request = Request::new(ListAdminRequest {
         email: Some("qq".to_string()),
         is_del: None,
@@ -42,8 +40,7 @@ async fn test_list_admin() {
 #[should_panic]
 #[tokio::test]
 async fn test_edit_admin() {
-    let client_url = get_service_url(util::Service::Admin).unwrap();
-    let mut client = AdminServiceClient::connect(client_url).await.unwrap();
+    let mut client = AdminServiceClient::new(connect(util::Service::Admin).unwrap());
     let request = Request::new(EditAdminRequest {
         id: 1,
         email: "cakeal@qq.com".to_string(),
@@ -66,8 +63,7 @@ async fn test_edit_admin() {
 
 #[tokio::test]
 async fn test_toggle_admin() {
-    let client_url = get_service_url(util::Service::Admin).unwrap();
-    let mut client = AdminServiceClient::connect(client_url).await.unwrap();
+    let mut client = AdminServiceClient::new(connect(util::Service::Admin).unwrap());
     let request = Request::new(ToggleAdminRequest { id: 1 });
     let reply = client.toggle_admin(request).await.unwrap();
     dbg!(reply.into_inner());
@@ -80,8 +76,7 @@ async fn test_toggle_admin() {
 
 #[tokio::test]
 async fn test_admin_exists() {
-    let client_url = get_service_url(util::Service::Admin).unwrap();
-    let mut client = AdminServiceClient::connect(client_url).await.unwrap();
+    let mut client = AdminServiceClient::new(connect(util::Service::Admin).unwrap());
     let request = Request::new(AdminExistsRequest {
         condition: Some(proto::admin_exists_request::Condition::Email(
             "cakeal@qq.com".to_string(),
@@ -99,8 +94,7 @@ async fn test_admin_exists() {
 
 #[tokio::test]
 async fn test_get_admin() {
-    let client_url = get_service_url(util::Service::Admin).unwrap();
-    let mut client = AdminServiceClient::connect(client_url).await.unwrap();
+    let mut client = AdminServiceClient::new(connect(util::Service::Admin).unwrap());
     let request = Request::new(GetAdminRequest {
         condition: Some(proto::get_admin_request::Condition::ById(ById {
             id: 1,
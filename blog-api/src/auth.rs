@@ -0,0 +1,78 @@
+// 后台接口的登录校验
+
+use crate::model::AppState;
+use axum::async_trait;
+use axum::extract::{FromRequestParts, Request};
+use axum::http::request::Parts;
+use axum::http::{header, StatusCode};
+use axum::middleware::Next;
+use axum::response::Response;
+use axum::Json;
+use proto::get_admin_request::{ById, Condition};
+use proto::GetAdminRequest;
+use serde_json::json;
+use util::token::Claims;
+
+/// 已登录的管理员，从 `Authorization: Bearer <token>` 中解析，
+/// token 有效期内管理员被禁用后同样拒绝
+pub struct AuthAdmin(pub Claims);
+
+#[async_trait]
+impl FromRequestParts<AppState> for AuthAdmin {
+    type Rejection = (StatusCode, Json<serde_json::Value>);
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        // 已经被中间件校验过的直接取出
+        if let Some(claims) = parts.extensions.get::<Claims>() {
+            return Ok(AuthAdmin(claims.clone()));
+        }
+        let token = parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or((
+                StatusCode::UNAUTHORIZED,
+                Json(json!({ "message": "Missing token" })),
+            ))?;
+        let claims = util::token::verify(token)
+            .map_err(|err| (StatusCode::UNAUTHORIZED, Json(json!({ "message": err }))))?;
+
+        let request = tonic::Request::new(GetAdminRequest {
+            condition: Some(Condition::ById(ById {
+                id: claims.sub,
+                is_del: Some(false),
+            })),
+        });
+        // 管理员不存在或已被禁用时 admin-srv 返回 InvalidArgument
+        match state.admin.clone().get_admin(request).await {
+            Ok(reply) if reply.get_ref().admin.is_some() => Ok(AuthAdmin(claims)),
+            Ok(_) => Err(disabled()),
+            Err(err) if err.code() == tonic::Code::InvalidArgument => Err(disabled()),
+            Err(err) => Err((
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "message": err.message() })),
+            )),
+        }
+    }
+}
+
+fn disabled() -> (StatusCode, Json<serde_json::Value>) {
+    (
+        StatusCode::UNAUTHORIZED,
+        Json(json!({ "message": "Admin is disabled" })),
+    )
+}
+
+/// require_auth 拒绝所有未登录的请求
+pub async fn require_auth(
+    AuthAdmin(claims): AuthAdmin,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    request.extensions_mut().insert(claims);
+    next.run(request).await
+}
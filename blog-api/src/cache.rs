@@ -0,0 +1,302 @@
+// 公开读接口的进程内响应缓存，支持 ETag 协商，写接口成功后按范围失效
+
+use axum::body::{to_bytes, Body, Bytes};
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderValue, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use util::config::HttpConfig;
+
+// 缓存范围，写接口按范围清除
+pub const POSTS: &str = "post";
+pub const TAGS: &str = "tag";
+pub const COMMENTS: &str = "comment";
+/// 文章中带有标签，标签列表中带有文章数，两者的修改一起失效
+pub const POST_WRITES: &[&str] = &[POSTS, TAGS];
+pub const COMMENT_WRITES: &[&str] = &[COMMENTS];
+
+/// 从缓存返回的响应带有这个扩展
+#[derive(Clone, Copy)]
+pub struct CacheHit;
+
+#[derive(Clone)]
+struct Entry {
+    body: Bytes,
+    content_type: Option<HeaderValue>,
+    etag: String,
+    inserted: Instant,
+    seq: u64,
+}
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<String, Entry>, // 范围:路径及查询参数 -> 响应
+    order: BTreeMap<u64, String>,    // 插入顺序，超出大小时先淘汰最早的
+    next_seq: u64,
+    bytes: usize,
+}
+
+impl CacheState {
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.order.remove(&entry.seq);
+            self.bytes -= entry.body.len();
+        }
+    }
+}
+
+pub struct ResponseCache {
+    ttl: Duration,    // 为 0 时不缓存，只做 ETag 协商
+    max_bytes: usize, // 缓存的响应体总大小上限
+    state: Mutex<CacheState>,
+}
+
+impl ResponseCache {
+    pub fn new(ttl: Duration, max_bytes: usize) -> Self {
+        Self {
+            ttl,
+            max_bytes,
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    /// from_config 按 http.cache_ttl 和 http.cache_max_bytes 创建
+    pub fn from_config(config: &HttpConfig) -> Self {
+        Self::new(config.cache_ttl(), config.cache_max_bytes)
+    }
+
+    fn get_at(&self, key: &str, now: Instant) -> Option<Entry> {
+        let mut state = self.state.lock().unwrap();
+        match state.entries.get(key) {
+            Some(entry) if now.duration_since(entry.inserted) < self.ttl => Some(entry.clone()),
+            Some(_) => {
+                state.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn insert_at(&self, key: String, mut entry: Entry, now: Instant) {
+        if self.ttl.is_zero() || entry.body.len() > self.max_bytes {
+            return;
+        }
+        let mut state = self.state.lock().unwrap();
+        state.remove(&key);
+        while state.bytes + entry.body.len() > self.max_bytes {
+            let Some((_, oldest)) = state.order.pop_first() else {
+                break;
+            };
+            if let Some(old) = state.entries.remove(&oldest) {
+                state.bytes -= old.body.len();
+            }
+        }
+        entry.inserted = now;
+        entry.seq = state.next_seq;
+        state.next_seq += 1;
+        state.bytes += entry.body.len();
+        state.order.insert(entry.seq, key.clone());
+        state.entries.insert(key, entry);
+    }
+
+    /// invalidate 清除这些范围内的全部缓存
+    pub fn invalidate(&self, scopes: &[&str]) {
+        let mut state = self.state.lock().unwrap();
+        let keys = state
+            .entries
+            .keys()
+            .filter(|key| {
+                scopes
+                    .iter()
+                    .any(|scope| key.split_once(':').map(|(s, _)| s) == Some(scope))
+            })
+            .cloned()
+            .collect::<Vec<String>>();
+        for key in keys {
+            state.remove(&key);
+        }
+    }
+
+    /// cache_control revalidate 时客户端每次都要用 ETag 协商，由 blog-api 决定是否返回 304
+    fn cache_control(&self, revalidate: bool) -> HeaderValue {
+        if revalidate || self.ttl.is_zero() {
+            HeaderValue::from_static("no-cache")
+        } else {
+            HeaderValue::from_str(&format!("public, max-age={}", self.ttl.as_secs())).unwrap()
+        }
+    }
+}
+
+impl Entry {
+    fn new(body: Bytes, content_type: Option<HeaderValue>) -> Self {
+        // 进程内的哈希，重启后 ETag 会变化，只会让客户端多取一次完整响应
+        let mut hasher = DefaultHasher::new();
+        body.hash(&mut hasher);
+        Self {
+            etag: format!("\"{:016x}\"", hasher.finish()),
+            body,
+            content_type,
+            inserted: Instant::now(),
+            seq: 0,
+        }
+    }
+}
+
+/// etag_matches If-None-Match 中有 * 或者相同的 ETag，弱比较
+fn etag_matches(if_none_match: &HeaderValue, etag: &str) -> bool {
+    let Ok(value) = if_none_match.to_str() else {
+        return false;
+    };
+    value
+        .split(',')
+        .map(str::trim)
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+}
+
+/// cache_response 公开读接口的缓存中间件，state 为缓存范围，用法：
+/// `get(handler).layer(middleware::from_fn_with_state(cache::POSTS, cache_response))`
+pub async fn cache_response(
+    State(scope): State<&'static str>,
+    request: Request,
+    next: Next,
+) -> Response {
+    cached_response(scope, false, request, next).await
+}
+
+/// revalidate_response 同 cache_response，但不允许客户端直接使用本地缓存，
+/// 用于命中缓存时也要记录浏览、或者修改后需要马上看到的接口
+pub async fn revalidate_response(
+    State(scope): State<&'static str>,
+    request: Request,
+    next: Next,
+) -> Response {
+    cached_response(scope, true, request, next).await
+}
+
+async fn cached_response(
+    scope: &'static str,
+    revalidate: bool,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(cache) = request.extensions().get::<Arc<ResponseCache>>().cloned() else {
+        return next.run(request).await;
+    };
+    if request.method() != Method::GET {
+        return next.run(request).await;
+    }
+    let key = format!("{}:{}", scope, request.uri());
+    let if_none_match = request.headers().get(header::IF_NONE_MATCH).cloned();
+    let now = Instant::now();
+
+    let (entry, hit) = match cache.get_at(&key, now) {
+        Some(entry) => (entry, true),
+        None => {
+            let response = next.run(request).await;
+            // 只缓存成功的响应
+            if response.status() != StatusCode::OK {
+                return response;
+            }
+            let (parts, body) = response.into_parts();
+            let body = match to_bytes(body, usize::MAX).await {
+                Ok(body) => body,
+                Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+            };
+            let entry = Entry::new(body, parts.headers.get(header::CONTENT_TYPE).cloned());
+            cache.insert_at(key, entry.clone(), now);
+            (entry, false)
+        }
+    };
+
+    let mut response = match if_none_match {
+        Some(value) if etag_matches(&value, &entry.etag) => {
+            StatusCode::NOT_MODIFIED.into_response()
+        }
+        _ => {
+            let mut response = Response::new(Body::from(entry.body));
+            if let Some(content_type) = entry.content_type {
+                response
+                    .headers_mut()
+                    .insert(header::CONTENT_TYPE, content_type);
+            }
+            response
+        }
+    };
+    let headers = response.headers_mut();
+    headers.insert(header::ETAG, HeaderValue::from_str(&entry.etag).unwrap());
+    headers.insert(header::CACHE_CONTROL, cache.cache_control(revalidate));
+    if hit {
+        response.extensions_mut().insert(CacheHit);
+    }
+    response
+}
+
+/// invalidate_cache 写接口成功后清除这些范围的缓存，用法：
+/// `post(handler).layer(middleware::from_fn_with_state(cache::POST_WRITES, invalidate_cache))`
+pub async fn invalidate_cache(
+    State(scopes): State<&'static [&'static str]>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let cache = request.extensions().get::<Arc<ResponseCache>>().cloned();
+    let response = next.run(request).await;
+    if let Some(cache) = cache {
+        if response.status().is_success() {
+            cache.invalidate(scopes);
+        }
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cache::{etag_matches, Entry, ResponseCache, POSTS, POST_WRITES};
+    use axum::body::Bytes;
+    use axum::http::HeaderValue;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_response_cache() {
+        let cache = ResponseCache::new(Duration::from_secs(10), 10);
+        let entry = |body: &'static str| Entry::new(Bytes::from(body), None);
+        let now = Instant::now();
+        cache.insert_at("post:/a".to_owned(), entry("aaaa"), now);
+        cache.insert_at("tag:/b".to_owned(), entry("bbbb"), now);
+        cache.insert_at("comment:/c".to_owned(), entry("cccc"), now);
+        // 超出总大小时淘汰最早的
+        assert!(cache.get_at("post:/a", now).is_none());
+        assert!(cache.get_at("tag:/b", now).is_some());
+        assert!(cache
+            .get_at("tag:/b", now + Duration::from_secs(10))
+            .is_none());
+
+        cache.insert_at("post:/a".to_owned(), entry("aaaa"), now);
+        cache.invalidate(POST_WRITES);
+        assert!(cache.get_at(&format!("{}:/a", POSTS), now).is_none());
+        assert!(cache.get_at("comment:/c", now).is_some());
+    }
+
+    #[test]
+    fn test_cache_control() {
+        let cache = ResponseCache::new(Duration::from_secs(30), 10);
+        assert_eq!(cache.cache_control(false), "public, max-age=30");
+        assert_eq!(cache.cache_control(true), "no-cache");
+        let cache = ResponseCache::new(Duration::ZERO, 10);
+        assert_eq!(cache.cache_control(false), "no-cache");
+    }
+
+    #[test]
+    fn test_etag_matches() {
+        let etag = "\"0123456789abcdef\"";
+        let header = |value: &'static str| HeaderValue::from_static(value);
+        assert!(etag_matches(&header("\"0123456789abcdef\""), etag));
+        assert!(etag_matches(&header("\"x\", W/\"0123456789abcdef\""), etag));
+        assert!(etag_matches(&header("*"), etag));
+        assert!(!etag_matches(&header("\"x\""), etag));
+    }
+}
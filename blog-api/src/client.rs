@@ -0,0 +1,184 @@
+// 客户端信息：真实 IP、User-Agent 以及是否为爬虫
+
+use axum::async_trait;
+use axum::extract::{ConnectInfo, FromRequestParts};
+use axum::http::request::Parts;
+use axum::http::{header, HeaderMap, StatusCode};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use util::config::{HttpConfig, ViewsConfig};
+
+// User-Agent 中包含这些词的视为爬虫或脚本
+const BOT_KEYWORDS: [&str; 10] = [
+    "bot",
+    "crawl",
+    "spider",
+    "slurp",
+    "preview",
+    "headless",
+    "facebookexternalhit",
+    "python-requests",
+    "curl/",
+    "wget/",
+];
+
+/// 受信任的反向代理，只有来自这些地址的 X-Forwarded-For 才会被采用
+#[derive(Default)]
+pub struct TrustedProxies(Vec<IpAddr>);
+
+impl TrustedProxies {
+    /// from_config 按 http.trusted_proxies 创建
+    pub fn from_config(config: &HttpConfig) -> Self {
+        Self(config.trusted_proxies.clone())
+    }
+
+    /// client_ip 直连地址是受信任的代理时，从 X-Forwarded-For 的最右边往左找第一个不受信任的地址
+    pub fn client_ip(&self, peer: IpAddr, headers: &HeaderMap) -> IpAddr {
+        if !self.0.contains(&peer) {
+            return peer;
+        }
+        let forwarded = headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .filter_map(|ip| ip.trim().parse::<IpAddr>().ok())
+            .collect::<Vec<IpAddr>>();
+        forwarded
+            .iter()
+            .rev()
+            .find(|ip| !self.0.contains(ip))
+            .or(forwarded.first())
+            .copied()
+            .unwrap_or(peer)
+    }
+}
+
+/// 计算访客标识的密钥，不直接把 IP 传给后端服务，也无法由访客标识反推 IP
+pub struct VisitorSalt(Vec<u8>);
+
+impl VisitorSalt {
+    /// from_config 使用 views.visitor_salt，没有设置时随机生成
+    pub fn from_config(config: &ViewsConfig) -> Self {
+        match config.visitor_salt.as_str() {
+            "" => Self(rand::random::<[u8; 32]>().to_vec()),
+            salt => Self(salt.as_bytes().to_vec()),
+        }
+    }
+
+    /// visitor_id 对 IP 和 User-Agent 做 HMAC-SHA256，取前 16 字节
+    fn visitor_id(&self, ip: IpAddr, user_agent: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.0).expect("HMAC accepts any key size");
+        mac.update(ip.to_string().as_bytes());
+        mac.update(b"\n");
+        mac.update(user_agent.as_bytes());
+        mac.finalize().into_bytes()[..16]
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+}
+
+pub struct ClientInfo {
+    pub ip: IpAddr,
+    pub user_agent: String,
+    pub visitor: String, // 由 IP 和 User-Agent 得到的访客标识
+}
+
+impl ClientInfo {
+    /// is_bot 没有 User-Agent 或 User-Agent 中包含爬虫关键字
+    pub fn is_bot(&self) -> bool {
+        let user_agent = self.user_agent.to_lowercase();
+        user_agent.is_empty() || BOT_KEYWORDS.iter().any(|k| user_agent.contains(k))
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ClientInfo
+where
+    S: Send + Sync,
+{
+    type Rejection = StatusCode;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let ConnectInfo(peer) = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .copied()
+            .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+        let ip = match parts.extensions.get::<Arc<TrustedProxies>>() {
+            Some(proxies) => proxies.client_ip(peer.ip(), &parts.headers),
+            None => peer.ip(),
+        };
+        let user_agent = parts
+            .headers
+            .get(header::USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_owned();
+        let visitor = parts
+            .extensions
+            .get::<Arc<VisitorSalt>>()
+            .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?
+            .visitor_id(ip, &user_agent);
+        Ok(ClientInfo {
+            ip,
+            user_agent,
+            visitor,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{ClientInfo, TrustedProxies, VisitorSalt};
+    use axum::http::HeaderMap;
+    use std::net::IpAddr;
+
+    #[test]
+    fn test_client_ip() {
+        let proxies = TrustedProxies(vec![
+            "10.0.0.1".parse().unwrap(),
+            "10.0.0.2".parse().unwrap(),
+        ]);
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            "1.1.1.1, 2.2.2.2, 10.0.0.2".parse().unwrap(),
+        );
+        // 不受信任的直连地址忽略 X-Forwarded-For
+        assert_eq!(
+            proxies.client_ip("3.3.3.3".parse().unwrap(), &headers),
+            "3.3.3.3".parse::<IpAddr>().unwrap()
+        );
+        assert_eq!(
+            proxies.client_ip("10.0.0.1".parse().unwrap(), &headers),
+            "2.2.2.2".parse::<IpAddr>().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_is_bot() {
+        let client = |user_agent: &str| ClientInfo {
+            ip: "1.1.1.1".parse().unwrap(),
+            user_agent: user_agent.to_owned(),
+            visitor: String::new(),
+        };
+        assert!(client("").is_bot());
+        assert!(client("Mozilla/5.0 (compatible; Googlebot/2.1)").is_bot());
+        assert!(!client("Mozilla/5.0 (X11; Linux x86_64) Firefox/131.0").is_bot());
+    }
+
+    #[test]
+    fn test_visitor_id() {
+        let salt = VisitorSalt(b"salt".to_vec());
+        let ip = "1.1.1.1".parse().unwrap();
+        let id = salt.visitor_id(ip, "Firefox");
+        assert_eq!(id.len(), 32);
+        assert_eq!(id, salt.visitor_id(ip, "Firefox"));
+        assert_ne!(id, salt.visitor_id(ip, "Chrome"));
+        assert_ne!(id, VisitorSalt(b"other".to_vec()).visitor_id(ip, "Firefox"));
+    }
+}
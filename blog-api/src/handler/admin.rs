@@ -0,0 +1,202 @@
+use crate::model::AppState;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use proto::get_admin_request::{ByAuth, ById, Condition};
+use proto::{
+    CreateAdminReply, CreateAdminRequest, EditAdminReply, EditAdminRequest, GetAdminReply,
+    GetAdminRequest, ListAdminReply, ListAdminRequest, ToggleAdminReply, ToggleAdminRequest,
+};
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Deserialize)]
+pub struct LoginJson {
+    email: String,
+    password: String,
+}
+
+/// 登录成功后返回 token，之后的后台请求需携带 `Authorization: Bearer <token>`
+pub async fn login(
+    mut state: State<AppState>,
+    Json(login): Json<LoginJson>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let request = tonic::Request::new(GetAdminRequest {
+        condition: Some(Condition::ByAuth(ByAuth {
+            email: login.email,
+            password: login.password,
+        })),
+    });
+    let GetAdminReply { admin } = state
+        .admin
+        .get_admin(request)
+        .await
+        .map_err(|err| {
+            (
+                StatusCode::UNAUTHORIZED,
+                Json(json!({ "message": err.message() })),
+            )
+        })?
+        .into_inner();
+    let admin = match admin {
+        Some(admin) if !admin.is_del => admin,
+        _ => {
+            return Err((
+                StatusCode::UNAUTHORIZED,
+                Json(json!({ "message": "Admin not found" })),
+            ))
+        }
+    };
+    let token = util::token::issue(admin.id, &admin.email).map_err(|err| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "message": err })),
+        )
+    })?;
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "token": token,
+            "admin": entity::model::Admin::from(admin),
+        })),
+    ))
+}
+
+#[derive(Deserialize)]
+pub struct NewAdminJson {
+    email: String,
+    password: String,
+}
+
+pub async fn new_admin(
+    mut state: State<AppState>,
+    Json(new_admin): Json<NewAdminJson>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let request = tonic::Request::new(CreateAdminRequest {
+        email: new_admin.email,
+        password: new_admin.password,
+    });
+    let CreateAdminReply { id } = state
+        .admin
+        .create_admin(request)
+        .await
+        .map_err(|err| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "message": err.message() })),
+            )
+        })?
+        .into_inner();
+    Ok((StatusCode::OK, Json(json!({ "id": id }))))
+}
+
+#[derive(Deserialize)]
+pub struct SearchParams {
+    email: Option<String>,
+    is_del: Option<bool>,
+}
+
+pub async fn search_admins(
+    mut state: State<AppState>,
+    Query(params): Query<SearchParams>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let request = tonic::Request::new(ListAdminRequest {
+        email: params.email,
+        is_del: params.is_del,
+    });
+    let ListAdminReply { admins } = state
+        .admin
+        .list_admin(request)
+        .await
+        .map_err(|err| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "message": err.message() })),
+            )
+        })?
+        .into_inner();
+    let admins: Vec<entity::model::Admin> = admins.into_iter().map(Into::into).collect();
+    Ok((StatusCode::OK, Json(json!(admins))))
+}
+
+#[derive(Deserialize)]
+pub struct EditAdminJson {
+    id: i32,
+    email: String,
+    password: String,
+    new_password: Option<String>,
+}
+
+pub async fn edit_admin(
+    mut state: State<AppState>,
+    Json(edit_admin): Json<EditAdminJson>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let request = tonic::Request::new(EditAdminRequest {
+        id: edit_admin.id,
+        email: edit_admin.email,
+        password: edit_admin.password,
+        new_password: edit_admin.new_password,
+    });
+    let EditAdminReply { id, ok } = state
+        .admin
+        .edit_admin(request)
+        .await
+        .map_err(|err| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "message": err.message() })),
+            )
+        })?
+        .into_inner();
+    Ok((StatusCode::OK, Json(json!({ "id": id, "ok": ok }))))
+}
+
+pub async fn toggle_admin(
+    mut state: State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let request = tonic::Request::new(ToggleAdminRequest { id });
+    let ToggleAdminReply { id, is_del } = state
+        .admin
+        .toggle_admin(request)
+        .await
+        .map_err(|err| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "message": err.message() })),
+            )
+        })?
+        .into_inner();
+    Ok((StatusCode::OK, Json(json!({ "id": id, "is_del": is_del }))))
+}
+
+pub async fn get_admin_info(
+    mut state: State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let request = tonic::Request::new(GetAdminRequest {
+        condition: Some(Condition::ById(ById { id, is_del: None })),
+    });
+    let GetAdminReply { admin } = state
+        .admin
+        .get_admin(request)
+        .await
+        .map_err(|err| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "message": err.message() })),
+            )
+        })?
+        .into_inner();
+    match admin {
+        Some(admin) => Ok((
+            StatusCode::OK,
+            Json(json!(entity::model::Admin::from(admin))),
+        )),
+        None => Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "message": "Admin not found" })),
+        )),
+    }
+}
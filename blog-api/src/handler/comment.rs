@@ -1,10 +1,12 @@
 use crate::model::AppState;
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
 use axum::Json;
 use proto::{
     CreateCommentReply, CreateCommentRequest, GetPostCommentsReply, GetPostCommentsRequest,
+    ListCommentsReply, ListCommentsRequest, SetCommentsStatusReply, SetCommentsStatusRequest,
+    ToggleCommentReply, ToggleCommentRequest,
 };
 use serde::Deserialize;
 use serde_json::json;
@@ -15,6 +17,7 @@ pub struct NewCommentJson {
     name: String,
     hashed_email: String,
     content: String,
+    parent_id: Option<i32>,
 }
 
 pub async fn new_comment(
@@ -26,8 +29,9 @@ pub async fn new_comment(
         name: new_comment.name,
         hashed_email: new_comment.hashed_email,
         content: new_comment.content,
+        parent_id: new_comment.parent_id,
     });
-    let CreateCommentReply { id } = state
+    let CreateCommentReply { id, status } = state
         .comment
         .create_comment(request)
         .await
@@ -38,15 +42,53 @@ pub async fn new_comment(
             )
         })?
         .into_inner();
-    Ok((StatusCode::OK, Json(json!({ "id": id }))))
+    let status = proto::CommentStatus::try_from(status)
+        .unwrap_or_default()
+        .as_str_name()
+        .to_lowercase();
+    Ok((StatusCode::OK, Json(json!({ "id": id, "status": status }))))
+}
+
+#[derive(Deserialize)]
+pub struct PostCommentsParams {
+    page: Option<i32>,
+    cursor: Option<String>, // 上一页返回的 next_cursor，设置时忽略 page
+    page_size: Option<i32>,
+    sort: Option<String>, // oldest 或 newest
 }
 
 pub async fn get_post_comments(
     mut state: State<AppState>,
     Path(post_id): Path<i32>,
+    Query(params): Query<PostCommentsParams>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
-    let request = tonic::Request::new(GetPostCommentsRequest { post_id });
-    let GetPostCommentsReply { comments } = state
+    let page = params.page.unwrap_or(1);
+    let sort = match params.sort {
+        Some(sort) => match proto::CommentSort::from_str_name(&sort.to_uppercase()) {
+            Some(sort) => Some(sort as i32),
+            None => {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    Json(json!({ "message": "Unknown sort" })),
+                ))
+            }
+        },
+        None => None,
+    };
+    let request = tonic::Request::new(GetPostCommentsRequest {
+        post_id,
+        page: Some(page - 1),
+        cursor: params.cursor,
+        page_size: params.page_size,
+        sort,
+    });
+    let GetPostCommentsReply {
+        comments,
+        total,
+        page,
+        page_total,
+        next_cursor,
+    } = state
         .comment
         .get_post_comments(request)
         .await
@@ -57,6 +99,113 @@ pub async fn get_post_comments(
             )
         })?
         .into_inner();
-    let comments: Vec<entity::model::Comment> = comments.into_iter().map(Into::into).collect();
-    Ok((StatusCode::OK, Json(json!(comments))))
+    let res = entity::model::PostCommentsRes {
+        page: page + 1,
+        page_total,
+        total,
+        comments: comments.into_iter().map(Into::into).collect(),
+        next_cursor,
+    };
+    Ok((StatusCode::OK, Json(json!(res))))
+}
+
+pub async fn toggle_comment(
+    mut state: State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let request = tonic::Request::new(ToggleCommentRequest { id });
+    let ToggleCommentReply { id, is_del } = state
+        .comment
+        .toggle_comment(request)
+        .await
+        .map_err(|err| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "message": err.message() })),
+            )
+        })?
+        .into_inner();
+    Ok((StatusCode::OK, Json(json!({ "id": id, "is_del": is_del }))))
+}
+
+#[derive(Deserialize)]
+pub struct ListCommentsParams {
+    status: Option<String>,
+    is_del: Option<bool>,
+    page: Option<i32>,
+}
+
+/// 后台审核列表，查询所有文章的评论
+pub async fn list_comments(
+    mut state: State<AppState>,
+    Query(params): Query<ListCommentsParams>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let page = params.page.unwrap_or(1);
+    let status = params.status.map(parse_status).transpose()?;
+    let request = tonic::Request::new(ListCommentsRequest {
+        status,
+        page: Some(page - 1),
+        is_del: params.is_del,
+    });
+    let ListCommentsReply {
+        page,
+        page_total,
+        comments,
+    } = state
+        .comment
+        .list_comments(request)
+        .await
+        .map_err(|err| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "message": err.message() })),
+            )
+        })?
+        .into_inner();
+    let res = entity::model::ListCommentsRes {
+        page: page + 1,
+        page_total,
+        comments: comments.into_iter().map(Into::into).collect(),
+    };
+    Ok((StatusCode::OK, Json(json!(res))))
+}
+
+#[derive(Deserialize)]
+pub struct SetCommentsStatusJson {
+    ids: Vec<i32>,
+    status: String,
+}
+
+/// 批量通过/拒绝评论，或标记为垃圾评论
+pub async fn set_comments_status(
+    mut state: State<AppState>,
+    Json(params): Json<SetCommentsStatusJson>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let request = tonic::Request::new(SetCommentsStatusRequest {
+        ids: params.ids,
+        status: parse_status(params.status)?,
+    });
+    let SetCommentsStatusReply { ids } = state
+        .comment
+        .set_comments_status(request)
+        .await
+        .map_err(|err| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "message": err.message() })),
+            )
+        })?
+        .into_inner();
+    Ok((StatusCode::OK, Json(json!({ "ids": ids }))))
+}
+
+/// parse_status 把 approved、pending、spam、rejected 转为 proto 中的状态
+fn parse_status(status: String) -> Result<i32, (StatusCode, Json<serde_json::Value>)> {
+    match proto::CommentStatus::from_str_name(&status.to_uppercase()) {
+        Some(status) => Ok(status as i32),
+        None => Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "message": "Unknown comment status" })),
+        )),
+    }
 }
@@ -0,0 +1,251 @@
+use crate::model::{AppState, SiteConfig};
+use atom_syndication::{Content, Entry, Feed, Link, Text};
+use axum::extract::{Path, State};
+use axum::http::{header, StatusCode};
+use axum::response::IntoResponse;
+use axum::Json;
+use chrono::{DateTime, FixedOffset, Utc};
+use proto::{GetTagInfoReply, GetTagInfoRequest};
+use rss::{Channel, Guid, Item};
+use serde_json::json;
+
+const RSS_CONTENT_TYPE: &str = "application/rss+xml; charset=utf-8";
+const ATOM_CONTENT_TYPE: &str = "application/atom+xml; charset=utf-8";
+
+/// 订阅源的标题和链接
+struct FeedMeta {
+    title: String,
+    link: String,      // 对应的页面
+    self_link: String, // 订阅源自身
+}
+
+pub async fn rss_feed(
+    mut state: State<AppState>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let posts = latest_posts(&mut state, None).await?;
+    let meta = site_meta(&state.site, "feed.xml");
+    Ok((
+        [(header::CONTENT_TYPE, RSS_CONTENT_TYPE)],
+        build_rss(&state.site, meta, posts),
+    ))
+}
+
+pub async fn atom_feed(
+    mut state: State<AppState>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let posts = latest_posts(&mut state, None).await?;
+    let meta = site_meta(&state.site, "atom.xml");
+    Ok((
+        [(header::CONTENT_TYPE, ATOM_CONTENT_TYPE)],
+        build_atom(&state.site, meta, posts),
+    ))
+}
+
+pub async fn tag_rss_feed(
+    mut state: State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let meta = tag_meta(&mut state, id, "feed.xml").await?;
+    let posts = latest_posts(&mut state, Some(id)).await?;
+    Ok((
+        [(header::CONTENT_TYPE, RSS_CONTENT_TYPE)],
+        build_rss(&state.site, meta, posts),
+    ))
+}
+
+pub async fn tag_atom_feed(
+    mut state: State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let meta = tag_meta(&mut state, id, "atom.xml").await?;
+    let posts = latest_posts(&mut state, Some(id)).await?;
+    Ok((
+        [(header::CONTENT_TYPE, ATOM_CONTENT_TYPE)],
+        build_atom(&state.site, meta, posts),
+    ))
+}
+
+/// latest_posts 最新发布的一页文章
+async fn latest_posts(
+    state: &mut AppState,
+    tag_id: Option<i32>,
+) -> Result<Vec<proto::Post>, (StatusCode, Json<serde_json::Value>)> {
+    let request = tonic::Request::new(proto::ListPostRequest {
+        page: None,
+        tag_id: tag_id.into_iter().collect(),
+        keyword: None,
+        is_del: Some(false),
+        dateline_range: None,
+        match_all_tags: None,
+        status: Some(proto::PostStatus::Published as i32),
+        sort: Some(proto::PostSort::Newest as i32),
+        with_content: Some(state.site.feed_full_content),
+        cursor: None,
+        page_size: None,
+        with_total: None,
+    });
+    let reply = state
+        .post
+        .list_posts(request)
+        .await
+        .map_err(|err| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "message": err.message() })),
+            )
+        })?
+        .into_inner();
+    Ok(reply.posts)
+}
+
+fn site_meta(site: &SiteConfig, file: &str) -> FeedMeta {
+    FeedMeta {
+        title: site.title.clone(),
+        link: site.url.clone(),
+        self_link: format!("{}/{}", site.url, file),
+    }
+}
+
+async fn tag_meta(
+    state: &mut AppState,
+    id: i32,
+    file: &str,
+) -> Result<FeedMeta, (StatusCode, Json<serde_json::Value>)> {
+    let request = tonic::Request::new(GetTagInfoRequest {
+        id,
+        is_del: Some(false),
+    });
+    let GetTagInfoReply { tag } = state
+        .tag
+        .get_tag_info(request)
+        .await
+        .map_err(|err| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "message": err.message() })),
+            )
+        })?
+        .into_inner();
+    let tag = tag.ok_or((
+        StatusCode::BAD_REQUEST,
+        Json(json!({ "message": "Tag not found" })),
+    ))?;
+    let link = state.site.tag_url(id);
+    Ok(FeedMeta {
+        title: format!("{} - {}", state.site.title, tag.name),
+        self_link: format!("{}/{}", link, file),
+        link,
+    })
+}
+
+fn build_rss(site: &SiteConfig, meta: FeedMeta, posts: Vec<proto::Post>) -> String {
+    let last_build_date = feed_updated(&posts).to_rfc2822();
+    let items = posts
+        .into_iter()
+        .map(|post| {
+            let link = site.post_url(post.id);
+            let pub_date = to_datetime(post.publish_time.map(|t| t.seconds)).to_rfc2822();
+            let description = match post.html_content {
+                Some(html) if site.feed_full_content => html,
+                _ => post.summary,
+            };
+            Item {
+                title: Some(post.title),
+                link: Some(link.clone()),
+                guid: Some(Guid {
+                    value: link,
+                    permalink: true,
+                }),
+                description: Some(description),
+                pub_date: Some(pub_date),
+                ..Default::default()
+            }
+        })
+        .collect::<Vec<Item>>();
+    let channel = Channel {
+        title: meta.title,
+        link: meta.link,
+        description: site.description.clone(),
+        last_build_date: Some(last_build_date),
+        items,
+        ..Default::default()
+    };
+    channel.to_string()
+}
+
+fn build_atom(site: &SiteConfig, meta: FeedMeta, posts: Vec<proto::Post>) -> String {
+    let updated = feed_updated(&posts);
+    let entries = posts
+        .into_iter()
+        .map(|post| {
+            let link = site.post_url(post.id);
+            let published = to_datetime(post.publish_time.map(|t| t.seconds));
+            let updated = to_datetime(post_updated(&post));
+            let content = match post.html_content {
+                Some(html) if site.feed_full_content => Some(Content {
+                    value: Some(html),
+                    content_type: Some("html".to_owned()),
+                    ..Default::default()
+                }),
+                _ => None,
+            };
+            Entry {
+                id: link.clone(),
+                title: Text::plain(post.title),
+                updated,
+                published: Some(published),
+                links: vec![Link {
+                    href: link,
+                    ..Default::default()
+                }],
+                summary: Some(Text::plain(post.summary)),
+                content,
+                ..Default::default()
+            }
+        })
+        .collect::<Vec<Entry>>();
+    let feed = Feed {
+        id: meta.self_link.clone(),
+        title: Text::plain(meta.title),
+        subtitle: Some(Text::plain(site.description.clone())),
+        updated,
+        links: vec![
+            Link {
+                href: meta.link,
+                ..Default::default()
+            },
+            Link {
+                href: meta.self_link,
+                rel: "self".to_owned(),
+                mime_type: Some("application/atom+xml".to_owned()),
+                ..Default::default()
+            },
+        ],
+        entries,
+        ..Default::default()
+    };
+    feed.to_string()
+}
+
+/// post_updated 文章的更新时间，没有修改过时为发布时间
+fn post_updated(post: &proto::Post) -> Option<i64> {
+    post.update_time
+        .as_ref()
+        .or(post.publish_time.as_ref())
+        .map(|t| t.seconds)
+}
+
+/// feed_updated 订阅源的更新时间，取文章中最晚的更新时间
+fn feed_updated(posts: &[proto::Post]) -> DateTime<FixedOffset> {
+    match posts.iter().filter_map(post_updated).max() {
+        Some(seconds) => to_datetime(Some(seconds)),
+        None => Utc::now().fixed_offset(),
+    }
+}
+
+fn to_datetime(seconds: Option<i64>) -> DateTime<FixedOffset> {
+    seconds
+        .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+        .unwrap_or_default()
+        .fixed_offset()
+}
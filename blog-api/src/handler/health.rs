@@ -0,0 +1,63 @@
+use crate::model::AppState;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use serde_json::{json, Map, Value};
+use std::time::Duration;
+use tokio::task::JoinSet;
+use tonic_health::pb::health_check_response::ServingStatus;
+use tonic_health::pb::HealthCheckRequest;
+
+const READY_TIMEOUT: Duration = Duration::from_secs(2); // 单个上游服务健康检查的超时
+
+/// 存活检查，进程能处理请求即返回 200，不检查上游服务
+pub async fn healthz() -> impl IntoResponse {
+    Json(json!({"status": "ok"}))
+}
+
+/// 就绪检查，同时检查 tag、post、comment、admin 服务的 gRPC 健康状态，
+/// 全部为 SERVING 时返回 200，否则返回 503，并列出各服务的状态
+pub async fn readyz(state: State<AppState>) -> impl IntoResponse {
+    let mut checks = JoinSet::new();
+    for (service, client) in state.health.iter() {
+        let (service, mut client) = (*service, client.clone());
+        checks.spawn(async move {
+            let request = HealthCheckRequest {
+                service: String::new(), // 为空时查询服务的整体状态
+            };
+            let status = match tokio::time::timeout(READY_TIMEOUT, client.check(request)).await {
+                Ok(Ok(reply)) if reply.get_ref().status() == ServingStatus::Serving => "serving",
+                Ok(Ok(_)) => "not_serving",
+                Ok(Err(err)) => {
+                    tracing::warn!("{} health check failed: {}", service.key(), err.message());
+                    "unreachable"
+                }
+                Err(_) => {
+                    tracing::warn!("{} health check timed out", service.key());
+                    "unreachable"
+                }
+            };
+            (service.key(), status)
+        });
+    }
+
+    let mut services = Map::new();
+    let mut ready = true;
+    while let Some(result) = checks.join_next().await {
+        let (key, status) = match result {
+            Ok(checked) => checked,
+            Err(_) => continue,
+        };
+        ready &= status == "serving";
+        services.insert(key.to_owned(), Value::from(status));
+    }
+    ready &= services.len() == state.health.len();
+
+    let (code, status) = if ready {
+        (StatusCode::OK, "ready")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "not_ready")
+    };
+    (code, Json(json!({"status": status, "services": services})))
+}
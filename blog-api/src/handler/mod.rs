@@ -1,3 +1,8 @@
+pub mod admin;
 pub mod post;
 pub mod tag;
-pub mod comment;
\ No newline at end of file
+pub mod comment;
+pub mod feed;
+pub mod sitemap;
+pub mod stats;
+pub mod health;
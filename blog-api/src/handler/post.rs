@@ -1,21 +1,25 @@
+use crate::auth::AuthAdmin;
+use crate::cache::CacheHit;
+use crate::client::ClientInfo;
 use crate::model::AppState;
-use axum::extract::{Path, Query, State};
+use axum::extract::{Path, Query, Request, State};
 use axum::http::StatusCode;
-use axum::response::IntoResponse;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
 use axum::Json;
 use proto::GetPostRequest;
 use serde::Deserialize;
 use serde_json::json;
-use tokio::fs::File;
-use tokio::io;
-use tokio::io::AsyncReadExt;
 
 #[derive(Deserialize)]
 pub struct SearchParams {
     keyword: Option<String>,
     tag_id: Option<i32>,
     date_range: Option<(i64, i64)>,
+    sort: Option<String>,
     page: Option<i32>,
+    cursor: Option<String>, // 上一页返回的 next_cursor，设置时忽略 page
+    page_size: Option<i32>,
 }
 
 pub async fn search_posts(
@@ -24,19 +28,29 @@ pub async fn search_posts(
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
     let page = params.page.unwrap_or(1);
     let date_range = util::i64_to_dateline_range(params.date_range);
+    let sort = parse_sort(params.sort)?;
 
     // 只查询没有标记为删除的
     let request = tonic::Request::new(proto::ListPostRequest {
         page: Some(page - 1),
-        tag_id: params.tag_id,
+        tag_id: params.tag_id.into_iter().collect(),
         keyword: params.keyword,
         is_del: Some(false),
         dateline_range: date_range,
+        match_all_tags: None,
+        status: Some(proto::PostStatus::Published as i32),
+        sort,
+        with_content: None,
+        with_total: Some(params.cursor.is_none()),
+        cursor: params.cursor,
+        page_size: params.page_size,
     });
     let proto::ListPostReply {
         posts,
         page,
         page_total,
+        next_cursor,
+        total,
     } = state
         .post
         .list_posts(request)
@@ -53,19 +67,25 @@ pub async fn search_posts(
         page: page + 1,
         page_total,
         posts: posts.into_iter().map(Into::into).collect(),
+        next_cursor,
+        total,
     };
 
     Ok((StatusCode::OK, Json(json!(res))))
 }
 
+/// 爬虫的访问不计入点击量，同一访客的重复访问由 post-srv 去重
 pub async fn get_single_post(
     mut state: State<AppState>,
+    client: ClientInfo,
     Path(id): Path<i32>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
     let request = tonic::Request::new(GetPostRequest {
         id,
         is_del: Some(false),
-        inc_hit: Some(true),
+        inc_hit: Some(!client.is_bot()),
+        status: Some(proto::PostStatus::Published as i32),
+        visitor: Some(client.visitor),
     });
     let proto::GetPostReply { post } = state
         .post
@@ -80,18 +100,14 @@ pub async fn get_single_post(
         .into_inner();
 
     match post {
-        Some(post) => {
-            let content = get_content(&post.html_path).await.map_err(|err| {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(json!({ "message": err.to_string() })),
-                )
-            })?;
+        Some(mut post) => {
+            let content = post.html_content.take().unwrap_or_default();
             Ok((
                 StatusCode::OK,
                 Json(json!(entity::model::GetPostRes {
                     post: post.into(),
                     content,
+                    md_content: None,
                 })),
             ))
         }
@@ -102,11 +118,351 @@ pub async fn get_single_post(
     }
 }
 
-async fn get_content(html_path: &str) -> io::Result<String> {
-    let mut content = String::new();
-    File::open(html_path)
-        .await?
-        .read_to_string(&mut content)
-        .await?;
-    Ok(content)
+/// record_cached_view 文章从缓存返回时不经过 get_single_post，单独记录一次浏览
+pub async fn record_cached_view(
+    State(mut state): State<AppState>,
+    client: ClientInfo,
+    Path(id): Path<i32>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let response = next.run(request).await;
+    if response.extensions().get::<CacheHit>().is_some() && !client.is_bot() {
+        let request = tonic::Request::new(proto::RecordPostViewRequest {
+            post_id: id,
+            visitor: Some(client.visitor),
+        });
+        if let Err(err) = state.post.record_post_view(request).await {
+            tracing::error!("Failed to record post view: {}", err.message());
+        }
+    }
+    response
+}
+
+#[derive(Deserialize)]
+pub struct NewPostJson {
+    title: String,
+    tag_id: Vec<i32>,
+    summary: Option<String>,
+    content: String,
+    status: Option<String>,
+    publish_time: Option<i64>,
+}
+
+pub async fn new_post(
+    mut state: State<AppState>,
+    AuthAdmin(claims): AuthAdmin,
+    Json(new_post): Json<NewPostJson>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let status = parse_status(new_post.status)?;
+    let request = tonic::Request::new(proto::CreatePostRequest {
+        title: new_post.title,
+        tag_id: new_post.tag_id,
+        summary: new_post.summary,
+        content: new_post.content,
+        editor_id: Some(claims.sub),
+        status,
+        publish_time: util::i64_to_timestamp(new_post.publish_time),
+    });
+    let proto::CreatePostReply { id } = state
+        .post
+        .create_post(request)
+        .await
+        .map_err(|err| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "message": err.message() })),
+            )
+        })?
+        .into_inner();
+    Ok((StatusCode::OK, Json(json!({ "id": id }))))
+}
+
+#[derive(Deserialize)]
+pub struct EditPostJson {
+    id: i32,
+    title: String,
+    tag_id: Vec<i32>,
+    summary: Option<String>,
+    content: String,
+    status: Option<String>,
+    publish_time: Option<i64>,
+}
+
+pub async fn edit_post(
+    mut state: State<AppState>,
+    AuthAdmin(claims): AuthAdmin,
+    Json(edit_post): Json<EditPostJson>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let status = parse_status(edit_post.status)?;
+    let request = tonic::Request::new(proto::EditPostRequest {
+        id: edit_post.id,
+        title: edit_post.title,
+        tag_id: edit_post.tag_id,
+        summary: edit_post.summary,
+        content: edit_post.content,
+        editor_id: Some(claims.sub),
+        status,
+        publish_time: util::i64_to_timestamp(edit_post.publish_time),
+    });
+    let proto::EditPostReply { id, ok } = state
+        .post
+        .edit_post(request)
+        .await
+        .map_err(|err| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "message": err.message() })),
+            )
+        })?
+        .into_inner();
+    Ok((StatusCode::OK, Json(json!({ "id": id, "ok": ok }))))
+}
+
+pub async fn toggle_post(
+    mut state: State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let request = tonic::Request::new(proto::TogglePostRequest { id });
+    let proto::TogglePostReply { id, is_del } = state
+        .post
+        .toggle_post(request)
+        .await
+        .map_err(|err| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "message": err.message() })),
+            )
+        })?
+        .into_inner();
+    Ok((StatusCode::OK, Json(json!({ "id": id, "is_del": is_del }))))
+}
+
+#[derive(Deserialize)]
+pub struct AdminSearchParams {
+    keyword: Option<String>,
+    tag_id: Option<i32>,
+    is_del: Option<bool>,
+    status: Option<String>,
+    sort: Option<String>,
+    page: Option<i32>,
+    cursor: Option<String>,
+    page_size: Option<i32>,
+}
+
+/// 后台搜索文章，可查询已删除的文章
+pub async fn admin_search_posts(
+    mut state: State<AppState>,
+    Query(params): Query<AdminSearchParams>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let page = params.page.unwrap_or(1);
+    let status = parse_status(params.status)?;
+    let sort = parse_sort(params.sort)?;
+
+    let request = tonic::Request::new(proto::ListPostRequest {
+        page: Some(page - 1),
+        tag_id: params.tag_id.into_iter().collect(),
+        keyword: params.keyword,
+        is_del: params.is_del,
+        dateline_range: None,
+        match_all_tags: None,
+        status,
+        sort,
+        with_content: None,
+        with_total: Some(params.cursor.is_none()),
+        cursor: params.cursor,
+        page_size: params.page_size,
+    });
+    let proto::ListPostReply {
+        posts,
+        page,
+        page_total,
+        next_cursor,
+        total,
+    } = state
+        .post
+        .list_posts(request)
+        .await
+        .map_err(|err| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "message": err.message() })),
+            )
+        })?
+        .into_inner();
+
+    let res = entity::model::ListPostRes {
+        page: page + 1,
+        page_total,
+        posts: posts.into_iter().map(Into::into).collect(),
+        next_cursor,
+        total,
+    };
+
+    Ok((StatusCode::OK, Json(json!(res))))
+}
+
+/// 后台获取文章，不增加点击量
+pub async fn admin_get_post(
+    mut state: State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let request = tonic::Request::new(GetPostRequest {
+        id,
+        is_del: None,
+        inc_hit: Some(false),
+        status: None,
+        visitor: None,
+    });
+    let proto::GetPostReply { post } = state
+        .post
+        .get_post(request)
+        .await
+        .map_err(|err| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "message": err.message() })),
+            )
+        })?
+        .into_inner();
+
+    match post {
+        Some(mut post) => {
+            let content = post.html_content.take().unwrap_or_default();
+            let md_content = post.md_content.take();
+            Ok((
+                StatusCode::OK,
+                Json(json!(entity::model::GetPostRes {
+                    post: post.into(),
+                    content,
+                    md_content,
+                })),
+            ))
+        }
+        None => Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "message": "Post not found" })),
+        )),
+    }
+}
+
+pub async fn list_revisions(
+    mut state: State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let request = tonic::Request::new(proto::ListPostRevisionsRequest { post_id: id });
+    let proto::ListPostRevisionsReply { revisions } = state
+        .post
+        .list_post_revisions(request)
+        .await
+        .map_err(|err| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "message": err.message() })),
+            )
+        })?
+        .into_inner();
+    let revisions: Vec<entity::model::PostRevision> =
+        revisions.into_iter().map(Into::into).collect();
+    Ok((StatusCode::OK, Json(json!(revisions))))
+}
+
+#[derive(Deserialize)]
+pub struct DiffParams {
+    from: i32,
+    to: i32,
+}
+
+pub async fn diff_revisions(
+    mut state: State<AppState>,
+    Query(params): Query<DiffParams>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let request = tonic::Request::new(proto::DiffPostRevisionsRequest {
+        from_id: params.from,
+        to_id: params.to,
+    });
+    let proto::DiffPostRevisionsReply { from, to, lines } = state
+        .post
+        .diff_post_revisions(request)
+        .await
+        .map_err(|err| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "message": err.message() })),
+            )
+        })?
+        .into_inner();
+    let res = entity::model::DiffPostRevisionsRes {
+        from: from.map(Into::into),
+        to: to.map(Into::into),
+        lines: lines.into_iter().map(Into::into).collect(),
+    };
+    Ok((StatusCode::OK, Json(json!(res))))
+}
+
+#[derive(Deserialize)]
+pub struct RestoreRevisionJson {
+    post_id: i32,
+    revision_id: i32,
+}
+
+pub async fn restore_revision(
+    mut state: State<AppState>,
+    AuthAdmin(claims): AuthAdmin,
+    Json(restore): Json<RestoreRevisionJson>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let request = tonic::Request::new(proto::RestorePostRevisionRequest {
+        post_id: restore.post_id,
+        revision_id: restore.revision_id,
+        editor_id: Some(claims.sub),
+    });
+    let proto::RestorePostRevisionReply {
+        id,
+        ok,
+        revision_id,
+    } = state
+        .post
+        .restore_post_revision(request)
+        .await
+        .map_err(|err| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "message": err.message() })),
+            )
+        })?
+        .into_inner();
+    Ok((
+        StatusCode::OK,
+        Json(json!({ "id": id, "ok": ok, "revision_id": revision_id })),
+    ))
+}
+
+/// parse_status 把 published、draft、scheduled、archived 转为 proto 中的状态
+fn parse_status(
+    status: Option<String>,
+) -> Result<Option<i32>, (StatusCode, Json<serde_json::Value>)> {
+    match status {
+        Some(status) => match proto::PostStatus::from_str_name(&status.to_uppercase()) {
+            Some(status) => Ok(Some(status as i32)),
+            None => Err((
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "message": "Unknown post status" })),
+            )),
+        },
+        None => Ok(None),
+    }
+}
+
+/// parse_sort 把 newest、relevance、most_hits 转为 proto 中的排序方式
+fn parse_sort(sort: Option<String>) -> Result<Option<i32>, (StatusCode, Json<serde_json::Value>)> {
+    match sort {
+        Some(sort) => match proto::PostSort::from_str_name(&sort.to_uppercase()) {
+            Some(sort) => Ok(Some(sort as i32)),
+            None => Err((
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "message": "Unknown sort" })),
+            )),
+        },
+        None => Ok(None),
+    }
 }
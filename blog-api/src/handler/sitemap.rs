@@ -0,0 +1,190 @@
+use crate::model::AppState;
+use axum::extract::{Path, State};
+use axum::http::{header, StatusCode};
+use axum::response::IntoResponse;
+use axum::Json;
+use chrono::DateTime;
+use proto::{ListPostSitemapReply, ListPostSitemapRequest, ListTagsReply, ListTagsRequest};
+use serde_json::json;
+use util::SITEMAP_MAX_URLS;
+
+const XML_CONTENT_TYPE: &str = "application/xml; charset=utf-8";
+
+/// 站点地图中的一个 URL
+struct SitemapUrl {
+    loc: String,
+    lastmod: Option<String>,
+}
+
+/// 文章较少时直接返回全部 URL，超过上限时返回 sitemap index，
+/// 分为 /sitemap/tags.xml 和 /sitemap/posts/1.xml、/sitemap/posts/2.xml ...
+pub async fn sitemap(
+    mut state: State<AppState>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let mut urls = tag_urls(&mut state).await?;
+    // 先只取总数，放得下时才取全部文章
+    let ListPostSitemapReply { total, .. } = post_entries(&mut state, 0, 1).await?;
+    let body = if urls.len() as i64 + total <= SITEMAP_MAX_URLS as i64 {
+        let ListPostSitemapReply { entries, .. } =
+            post_entries(&mut state, 0, SITEMAP_MAX_URLS).await?;
+        urls.extend(post_urls(&state, entries));
+        urlset(urls)
+    } else {
+        let files = (total + SITEMAP_MAX_URLS as i64 - 1) / SITEMAP_MAX_URLS as i64;
+        let mut locs = vec![format!("{}/sitemap/tags.xml", state.site.url)];
+        locs.extend((1..=files).map(|i| format!("{}/sitemap/posts/{}.xml", state.site.url, i)));
+        sitemap_index(locs)
+    };
+    Ok(([(header::CONTENT_TYPE, XML_CONTENT_TYPE)], body))
+}
+
+pub async fn tag_sitemap(
+    mut state: State<AppState>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let urls = tag_urls(&mut state).await?;
+    Ok(([(header::CONTENT_TYPE, XML_CONTENT_TYPE)], urlset(urls)))
+}
+
+/// file 为 1.xml、2.xml ...
+pub async fn post_sitemap(
+    mut state: State<AppState>,
+    Path(file): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let page = file
+        .strip_suffix(".xml")
+        .and_then(|page| page.parse::<i32>().ok())
+        .filter(|page| *page >= 1)
+        .ok_or((
+            StatusCode::NOT_FOUND,
+            Json(json!({ "message": "Sitemap not found" })),
+        ))?;
+    let ListPostSitemapReply { entries, .. } =
+        post_entries(&mut state, page - 1, SITEMAP_MAX_URLS).await?;
+    if entries.is_empty() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({ "message": "Sitemap not found" })),
+        ));
+    }
+    let urls = post_urls(&state, entries).collect();
+    Ok(([(header::CONTENT_TYPE, XML_CONTENT_TYPE)], urlset(urls)))
+}
+
+pub async fn robots(state: State<AppState>) -> impl IntoResponse {
+    let mut body = String::from("User-agent: *\n");
+    for path in &state.site.robots_allow {
+        body.push_str(&format!("Allow: {}\n", path));
+    }
+    for path in &state.site.robots_disallow {
+        body.push_str(&format!("Disallow: {}\n", path));
+    }
+    body.push_str(&format!("\nSitemap: {}/sitemap.xml\n", state.site.url));
+    ([(header::CONTENT_TYPE, "text/plain; charset=utf-8")], body)
+}
+
+/// tag_urls 首页和所有标签页
+async fn tag_urls(
+    state: &mut AppState,
+) -> Result<Vec<SitemapUrl>, (StatusCode, Json<serde_json::Value>)> {
+    let request = tonic::Request::new(ListTagsRequest {
+        name: None,
+        is_del: Some(false),
+    });
+    let ListTagsReply { tags } = state
+        .tag
+        .list_tags(request)
+        .await
+        .map_err(|err| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "message": err.message() })),
+            )
+        })?
+        .into_inner();
+    let mut urls = vec![SitemapUrl {
+        loc: format!("{}/", state.site.url),
+        lastmod: None,
+    }];
+    urls.extend(tags.into_iter().map(|tag| SitemapUrl {
+        loc: state.site.tag_url(tag.id),
+        lastmod: None,
+    }));
+    Ok(urls)
+}
+
+async fn post_entries(
+    state: &mut AppState,
+    page: i32,
+    page_size: i32,
+) -> Result<ListPostSitemapReply, (StatusCode, Json<serde_json::Value>)> {
+    let request = tonic::Request::new(ListPostSitemapRequest {
+        page: Some(page),
+        page_size,
+    });
+    let reply = state
+        .post
+        .list_post_sitemap(request)
+        .await
+        .map_err(|err| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "message": err.message() })),
+            )
+        })?
+        .into_inner();
+    Ok(reply)
+}
+
+fn post_urls<'a>(
+    state: &'a AppState,
+    entries: Vec<proto::SitemapEntry>,
+) -> impl Iterator<Item = SitemapUrl> + 'a {
+    entries.into_iter().map(|entry| SitemapUrl {
+        loc: state.site.post_url(entry.id),
+        lastmod: entry
+            .lastmod
+            .and_then(|t| DateTime::from_timestamp(t.seconds, 0))
+            .map(|t| t.to_rfc3339()),
+    })
+}
+
+fn urlset(urls: Vec<SitemapUrl>) -> String {
+    let mut xml = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+"#,
+    );
+    for url in urls {
+        xml.push_str(&format!("<url><loc>{}</loc>", escape_xml(&url.loc)));
+        if let Some(lastmod) = url.lastmod {
+            xml.push_str(&format!("<lastmod>{}</lastmod>", lastmod));
+        }
+        xml.push_str("</url>\n");
+    }
+    xml.push_str("</urlset>\n");
+    xml
+}
+
+fn sitemap_index(locs: Vec<String>) -> String {
+    let mut xml = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+"#,
+    );
+    for loc in locs {
+        xml.push_str(&format!(
+            "<sitemap><loc>{}</loc></sitemap>\n",
+            escape_xml(&loc)
+        ));
+    }
+    xml.push_str("</sitemapindex>\n");
+    xml
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
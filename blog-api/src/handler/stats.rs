@@ -0,0 +1,135 @@
+// 后台统计面板使用的接口
+
+use crate::model::AppState;
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use proto::{
+    CountTagPostsReply, CountTagPostsRequest, GetDailyCommentsReply, GetDailyCommentsRequest,
+    GetDailyViewsReply, GetDailyViewsRequest, ListTopPostsReply, ListTopPostsRequest,
+};
+use serde::Deserialize;
+use serde_json::json;
+
+/// start、end 为 YYYY-MM-DD，包含两端，默认最近 30 天
+#[derive(Deserialize)]
+pub struct DailyParams {
+    post_id: Option<i32>, // 不设置时统计全站
+    start: Option<String>,
+    end: Option<String>,
+}
+
+/// 每天的浏览量
+pub async fn daily_views(
+    mut state: State<AppState>,
+    Query(params): Query<DailyParams>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let request = tonic::Request::new(GetDailyViewsRequest {
+        post_id: params.post_id,
+        start: params.start.unwrap_or_default(),
+        end: params.end.unwrap_or_default(),
+    });
+    let GetDailyViewsReply { days } = state
+        .post
+        .get_daily_views(request)
+        .await
+        .map_err(|err| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "message": err.message() })),
+            )
+        })?
+        .into_inner();
+    let days = days
+        .into_iter()
+        .map(Into::into)
+        .collect::<Vec<entity::model::DailyCount>>();
+    Ok((StatusCode::OK, Json(json!({ "days": days }))))
+}
+
+/// 每天的评论数
+pub async fn daily_comments(
+    mut state: State<AppState>,
+    Query(params): Query<DailyParams>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let request = tonic::Request::new(GetDailyCommentsRequest {
+        post_id: params.post_id,
+        start: params.start.unwrap_or_default(),
+        end: params.end.unwrap_or_default(),
+    });
+    let GetDailyCommentsReply { days } = state
+        .comment
+        .get_daily_comments(request)
+        .await
+        .map_err(|err| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "message": err.message() })),
+            )
+        })?
+        .into_inner();
+    let days = days
+        .into_iter()
+        .map(Into::into)
+        .collect::<Vec<entity::model::DailyCount>>();
+    Ok((StatusCode::OK, Json(json!({ "days": days }))))
+}
+
+#[derive(Deserialize)]
+pub struct TopPostsParams {
+    start: Option<String>,
+    end: Option<String>,
+    limit: Option<i32>,
+}
+
+/// 一段时间内浏览量最多的文章
+pub async fn top_posts(
+    mut state: State<AppState>,
+    Query(params): Query<TopPostsParams>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let request = tonic::Request::new(ListTopPostsRequest {
+        start: params.start.unwrap_or_default(),
+        end: params.end.unwrap_or_default(),
+        limit: params.limit,
+    });
+    let ListTopPostsReply { posts } = state
+        .post
+        .list_top_posts(request)
+        .await
+        .map_err(|err| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "message": err.message() })),
+            )
+        })?
+        .into_inner();
+    let posts = posts
+        .into_iter()
+        .map(Into::into)
+        .collect::<Vec<entity::model::TopPost>>();
+    Ok((StatusCode::OK, Json(json!({ "posts": posts }))))
+}
+
+/// 每个标签下的文章数
+pub async fn tag_posts(
+    mut state: State<AppState>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let request = tonic::Request::new(CountTagPostsRequest {});
+    let CountTagPostsReply { tags } = state
+        .post
+        .count_tag_posts(request)
+        .await
+        .map_err(|err| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "message": err.message() })),
+            )
+        })?
+        .into_inner();
+    let tags = tags
+        .into_iter()
+        .map(Into::into)
+        .collect::<Vec<entity::model::TagPostCount>>();
+    Ok((StatusCode::OK, Json(json!({ "tags": tags }))))
+}
@@ -3,7 +3,10 @@ use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
 use axum::Json;
-use proto::{GetTagInfoReply, GetTagInfoRequest, ListTagsReply, ListTagsRequest};
+use proto::{
+    CreateTagReply, CreateTagRequest, EditTagReply, EditTagRequest, GetTagInfoReply,
+    GetTagInfoRequest, ListTagsReply, ListTagsRequest, ToggleTagReply, ToggleTagRequest,
+};
 use serde::{Deserialize};
 use serde_json::json;
 
@@ -62,3 +65,104 @@ pub async fn get_tag_info(
         )),
     }
 }
+
+#[derive(Deserialize)]
+pub struct NewTagJson {
+    name: String,
+}
+
+pub async fn new_tag(
+    mut state: State<AppState>,
+    Json(new_tag): Json<NewTagJson>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let request = tonic::Request::new(CreateTagRequest { name: new_tag.name });
+    let CreateTagReply { id } = state
+        .tag
+        .create_tag(request)
+        .await
+        .map_err(|err| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "message": err.message() })),
+            )
+        })?
+        .into_inner();
+    Ok((StatusCode::OK, Json(json!({ "id": id }))))
+}
+
+#[derive(Deserialize)]
+pub struct EditTagJson {
+    id: i32,
+    name: String,
+}
+
+pub async fn edit_tag(
+    mut state: State<AppState>,
+    Json(edit_tag): Json<EditTagJson>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let request = tonic::Request::new(EditTagRequest {
+        id: edit_tag.id,
+        name: edit_tag.name,
+    });
+    let EditTagReply { id, ok } = state
+        .tag
+        .edit_tag(request)
+        .await
+        .map_err(|err| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "message": err.message() })),
+            )
+        })?
+        .into_inner();
+    Ok((StatusCode::OK, Json(json!({ "id": id, "ok": ok }))))
+}
+
+pub async fn toggle_tag(
+    mut state: State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let request = tonic::Request::new(ToggleTagRequest { id });
+    let ToggleTagReply { id, is_del } = state
+        .tag
+        .toggle_tag(request)
+        .await
+        .map_err(|err| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "message": err.message() })),
+            )
+        })?
+        .into_inner();
+    Ok((StatusCode::OK, Json(json!({ "id": id, "is_del": is_del }))))
+}
+
+#[derive(Deserialize)]
+pub struct AdminSearchParams {
+    name: Option<String>,
+    is_del: Option<bool>,
+}
+
+/// 后台搜索标签，可查询已删除的标签
+pub async fn admin_search_tags(
+    mut state: State<AppState>,
+    Query(params): Query<AdminSearchParams>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let request = tonic::Request::new(ListTagsRequest {
+        name: params.name,
+        is_del: params.is_del,
+    });
+    let ListTagsReply { tags } = state
+        .tag
+        .list_tags(request)
+        .await
+        .map_err(|err| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "message": err.message() })),
+            )
+        })?
+        .into_inner();
+    let tags: Vec<entity::model::Tag> = tags.into_iter().map(Into::into).collect();
+    Ok((StatusCode::OK, Json(json!(tags))))
+}
@@ -1,43 +1,89 @@
+use crate::cache::ResponseCache;
+use crate::client::{TrustedProxies, VisitorSalt};
 use crate::model::AppState;
-use axum::Router;
+use crate::rate_limit::{MemoryStore, RateLimiter};
+use axum::{middleware, Extension, Router};
 use axum::routing::any;
+use axum_server::tls_rustls::RustlsConfig;
 use tower_http::trace;
 use tower_http::trace::TraceLayer;
 use tracing::Level;
 use crate::router::*;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use util::metrics::track_http;
 
+mod auth;
+mod cache;
+mod client;
 mod model;
+mod rate_limit;
 mod router;
 mod handler;
 
 #[tokio::main]
 async fn start() -> Result<(), Box<dyn std::error::Error>> {
-    let app_state = AppState::new().await?;
+    let config = util::telemetry::init(util::Service::BlogApi)?;
+    let app_state = AppState::new(config)?;
+    let rate_limiter = RateLimiter::from_config(Arc::new(MemoryStore::default()), &config.http);
+    let trusted_proxies = TrustedProxies::from_config(&config.http);
+    let response_cache = ResponseCache::from_config(&config.http);
+    let visitor_salt = VisitorSalt::from_config(&config.views);
 
     let app = Router::new()
-        .merge(post_routes())
+        .merge(post_routes(app_state.clone()))
         .merge(tag_routes())
         .merge(comment_routes())
+        .merge(admin_routes(app_state.clone()))
+        .merge(feed_routes())
+        .merge(sitemap_routes())
+        .merge(health_routes())
         .fallback(any(route_not_found))
-        .with_state(app_state).layer(
-        TraceLayer::new_for_http()
-            .make_span_with(trace::DefaultMakeSpan::new().level(Level::INFO))
-            .on_response(trace::DefaultOnResponse::new().level(Level::INFO)),
-    );
+        .with_state(app_state)
+        .layer(Extension(Arc::new(rate_limiter)))
+        .layer(Extension(Arc::new(trusted_proxies)))
+        .layer(Extension(Arc::new(visitor_salt)))
+        .layer(Extension(Arc::new(response_cache)))
+        .layer(middleware::from_fn(track_http))
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(util::telemetry::http_span)
+                .on_response(trace::DefaultOnResponse::new().level(Level::INFO)),
+        );
 
-    let addr = util::get_service_addr(util::Service::BlogApi)?;
-    let listener = tokio::net::TcpListener::bind(&addr).await?;
-    tracing::info!("Blog api listening on {}", addr);
-    axum::serve(listener, app).await?;
+    let service = config.service(util::Service::BlogApi);
+    let addr = service.bind.parse::<SocketAddr>()?;
+    let app = app.into_make_service_with_connect_info::<SocketAddr>();
+    match (&service.tls.cert, &service.tls.key) {
+        (Some(cert), Some(key)) => {
+            let tls = RustlsConfig::from_pem_file(cert, key).await?;
+            tracing::info!("Blog api listening on https://{}", addr);
+            let handle = axum_server::Handle::new();
+            tokio::spawn({
+                let handle = handle.clone();
+                async move {
+                    util::shutdown_signal().await;
+                    handle.graceful_shutdown(None);
+                }
+            });
+            axum_server::bind_rustls(addr, tls)
+                .handle(handle)
+                .serve(app)
+                .await?;
+        }
+        _ => {
+            let listener = tokio::net::TcpListener::bind(addr).await?;
+            tracing::info!("Blog api listening on {}", addr);
+            axum::serve(listener, app)
+                .with_graceful_shutdown(util::shutdown_signal())
+                .await?;
+        }
+    }
+    util::telemetry::shutdown().await;
     Ok(())
 }
 
 fn main() {
-    tracing_subscriber::fmt()
-        .with_target(false)
-        .compact()
-        .init();
-
     let _ = start().map_err(|err| {
         tracing::error!("{}", err);
     });
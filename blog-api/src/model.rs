@@ -1,20 +1,74 @@
+use proto::admin_service_client::AdminServiceClient;
 use proto::comment_service_client::CommentServiceClient;
 use proto::post_service_client::PostServiceClient;
 use proto::tag_service_client::TagServiceClient;
-use util::{get_service_url, Service};
+use std::sync::Arc;
+use tonic_health::pb::health_client::HealthClient;
+use util::config::Config;
+use util::interceptor::{connect, AuthChannel};
+use util::Service;
 
 #[derive(Clone)]
 pub struct AppState {
-    pub comment: CommentServiceClient<tonic::transport::Channel>,
-    pub post: PostServiceClient<tonic::transport::Channel>,
-    pub tag: TagServiceClient<tonic::transport::Channel>,
+    pub comment: CommentServiceClient<AuthChannel>,
+    pub post: PostServiceClient<AuthChannel>,
+    pub tag: TagServiceClient<AuthChannel>,
+    pub admin: AdminServiceClient<AuthChannel>,
+    pub site: Arc<SiteConfig>,
+    pub health: Arc<Vec<(Service, HealthClient<AuthChannel>)>>, // /readyz 检查的上游服务
+}
+
+/// 站点信息，用于生成订阅源等面向外部的链接
+pub struct SiteConfig {
+    pub url: String, // 前端站点地址，不以 / 结尾
+    pub title: String,
+    pub description: String,
+    pub feed_full_content: bool, // 订阅源输出全文还是摘要
+    pub robots_allow: Vec<String>,
+    pub robots_disallow: Vec<String>,
+}
+
+impl SiteConfig {
+    pub fn from_config(config: &Config) -> Self {
+        let site = &config.site;
+        Self {
+            url: site.base_url().to_owned(),
+            title: site.title.clone(),
+            description: site.description.clone(),
+            feed_full_content: config.feature(Service::BlogApi, "feed_full_content"),
+            robots_allow: site.robots_allow.clone(),
+            robots_disallow: site.robots_disallow.clone(),
+        }
+    }
+
+    pub fn post_url(&self, id: i32) -> String {
+        format!("{}/post/{}", self.url, id)
+    }
+
+    pub fn tag_url(&self, id: i32) -> String {
+        format!("{}/tag/{}", self.url, id)
+    }
 }
 
 impl AppState {
-    pub async fn new() -> Result<Self, Box<dyn std::error::Error>> {
-        let comment = CommentServiceClient::connect(get_service_url(Service::Comment)?).await?;
-        let post = PostServiceClient::connect(get_service_url(Service::Post)?).await?;
-        let tag = TagServiceClient::connect(get_service_url(Service::Tag)?).await?;
-        Ok(Self { comment, post, tag })
+    pub fn new(config: &Config) -> Result<Self, Box<dyn std::error::Error>> {
+        let comment_channel = connect(Service::Comment)?;
+        let post_channel = connect(Service::Post)?;
+        let tag_channel = connect(Service::Tag)?;
+        let admin_channel = connect(Service::Admin)?;
+        let health = vec![
+            (Service::Tag, HealthClient::new(tag_channel.clone())),
+            (Service::Post, HealthClient::new(post_channel.clone())),
+            (Service::Comment, HealthClient::new(comment_channel.clone())),
+            (Service::Admin, HealthClient::new(admin_channel.clone())),
+        ];
+        Ok(Self {
+            comment: CommentServiceClient::new(comment_channel),
+            post: PostServiceClient::new(post_channel),
+            tag: TagServiceClient::new(tag_channel),
+            admin: AdminServiceClient::new(admin_channel),
+            site: Arc::new(SiteConfig::from_config(config)),
+            health: Arc::new(health),
+        })
     }
 }
@@ -0,0 +1,219 @@
+// 按客户端 IP 的令牌桶限流
+
+use crate::client::ClientInfo;
+use axum::async_trait;
+use axum::extract::{Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use util::config::{HttpConfig, RateLimit};
+
+const MAX_BUCKETS: usize = 10000; // 超过后清理已经回满的令牌桶，仍然太多时丢弃最快回满的一半
+
+/// 每个桶最多 capacity 个令牌，每 period 回满
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limit {
+    pub capacity: u32,
+    pub period: Duration,
+}
+
+impl From<RateLimit> for Limit {
+    fn from(limit: RateLimit) -> Self {
+        Self {
+            capacity: limit.capacity,
+            period: limit.period,
+        }
+    }
+}
+
+impl Limit {
+    fn tokens_per_sec(&self) -> f64 {
+        self.capacity as f64 / self.period.as_secs_f64()
+    }
+}
+
+/// 令牌桶的存储，目前只有进程内的实现，多实例部署时可以换成共享的存储
+#[async_trait]
+pub trait RateLimitStore: Send + Sync {
+    /// acquire 取出一个令牌，令牌不足时返回还需要等待的时间
+    async fn acquire(&self, key: &str, limit: Limit) -> Result<(), Duration>;
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+    full_at: Instant, // 回满的时间，之后的桶和新建的一样，可以清理
+}
+
+pub struct MemoryStore {
+    max_buckets: usize,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl Default for MemoryStore {
+    fn default() -> Self {
+        Self {
+            max_buckets: MAX_BUCKETS,
+            buckets: Mutex::default(),
+        }
+    }
+}
+
+impl MemoryStore {
+    fn acquire_at(&self, key: &str, limit: Limit, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() >= self.max_buckets && !buckets.contains_key(key) {
+            buckets.retain(|_, bucket| bucket.full_at > now);
+            if buckets.len() >= self.max_buckets {
+                let mut full_at = buckets
+                    .values()
+                    .map(|bucket| bucket.full_at)
+                    .collect::<Vec<Instant>>();
+                let middle = full_at.len() / 2;
+                let (_, &mut cutoff, _) = full_at.select_nth_unstable(middle);
+                buckets.retain(|_, bucket| bucket.full_at > cutoff);
+            }
+        }
+        let bucket = buckets.entry(key.to_owned()).or_insert(Bucket {
+            tokens: limit.capacity as f64,
+            updated: now,
+            full_at: now,
+        });
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens =
+            (bucket.tokens + elapsed * limit.tokens_per_sec()).min(limit.capacity as f64);
+        bucket.updated = now;
+        let res = if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) / limit.tokens_per_sec(),
+            ))
+        };
+        bucket.full_at = now
+            + Duration::from_secs_f64(
+                (limit.capacity as f64 - bucket.tokens) / limit.tokens_per_sec(),
+            );
+        res
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for MemoryStore {
+    async fn acquire(&self, key: &str, limit: Limit) -> Result<(), Duration> {
+        self.acquire_at(key, limit, Instant::now())
+    }
+}
+
+pub struct RateLimiter {
+    store: Arc<dyn RateLimitStore>,
+    limits: HashMap<String, Limit>, // 路由名 -> 限制，没有配置的路由不限流
+}
+
+impl RateLimiter {
+    /// from_config 按 http.rate_limits 创建，格式在加载配置时已经校验过
+    pub fn from_config(store: Arc<dyn RateLimitStore>, config: &HttpConfig) -> Self {
+        let limits = config
+            .rate_limits
+            .iter()
+            .map(|(route, &limit)| (route.clone(), limit.into()))
+            .collect();
+        Self { store, limits }
+    }
+}
+
+/// rate_limit 路由限流中间件，state 为路由名，用法：
+/// `post(handler).layer(middleware::from_fn_with_state("new_comment", rate_limit))`
+pub async fn rate_limit(
+    State(route): State<&'static str>,
+    client: ClientInfo,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(limiter) = request.extensions().get::<Arc<RateLimiter>>().cloned() else {
+        return next.run(request).await;
+    };
+    let Some(&limit) = limiter.limits.get(route) else {
+        return next.run(request).await;
+    };
+    match limiter
+        .store
+        .acquire(&format!("{}:{}", route, client.ip), limit)
+        .await
+    {
+        Ok(()) => next.run(request).await,
+        Err(wait) => {
+            let retry_after = wait.as_secs_f64().ceil().max(1.0) as u64;
+            (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(header::RETRY_AFTER, retry_after.to_string())],
+                Json(json!({ "message": "Too many requests" })),
+            )
+                .into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rate_limit::{Limit, MemoryStore};
+    use std::time::{Duration, Instant};
+
+    fn limit(capacity: u32, seconds: u64) -> Limit {
+        Limit {
+            capacity,
+            period: Duration::from_secs(seconds),
+        }
+    }
+
+    #[test]
+    fn test_memory_store() {
+        let store = MemoryStore::default();
+        let limit = limit(2, 10);
+        let now = Instant::now();
+        assert!(store.acquire_at("a", limit, now).is_ok());
+        assert!(store.acquire_at("a", limit, now).is_ok());
+        assert_eq!(
+            store.acquire_at("a", limit, now),
+            Err(Duration::from_secs(5))
+        );
+        assert!(store.acquire_at("b", limit, now).is_ok());
+        assert!(store
+            .acquire_at("a", limit, now + Duration::from_secs(5))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_memory_store_eviction() {
+        let store = MemoryStore {
+            max_buckets: 4,
+            ..MemoryStore::default()
+        };
+        let now = Instant::now();
+        // 周期短的桶先回满，不受当前路由周期的影响
+        assert!(store.acquire_at("short", limit(1, 1), now).is_ok());
+        assert!(store.acquire_at("long", limit(1, 3600), now).is_ok());
+        for key in ["a", "b"] {
+            assert!(store.acquire_at(key, limit(1, 60), now).is_ok());
+        }
+        let later = now + Duration::from_secs(2);
+        assert!(store.acquire_at("c", limit(1, 1), later).is_ok());
+        {
+            let buckets = store.buckets.lock().unwrap();
+            assert!(!buckets.contains_key("short"));
+            assert!(buckets.contains_key("long"));
+        }
+        // 都没有回满时丢弃最快回满的一半，数量不超过上限
+        for key in ["d", "e", "f", "g"] {
+            assert!(store.acquire_at(key, limit(1, 60), later).is_ok());
+            assert!(store.buckets.lock().unwrap().len() <= 4);
+        }
+        assert!(store.acquire_at("long", limit(1, 3600), later).is_err());
+    }
+}
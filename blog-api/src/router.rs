@@ -1,30 +1,183 @@
-use crate::handler::{comment::*, post::*, tag::*};
+use crate::auth::require_auth;
+use crate::cache::{self, cache_response, invalidate_cache, revalidate_response};
+use crate::handler::{
+    admin::*, comment::*, feed::*, health::*, post::*, sitemap::*, stats::*, tag::*,
+};
 use crate::model::AppState;
+use crate::rate_limit::rate_limit;
 use axum::http::StatusCode;
+use axum::middleware;
 use axum::routing::{get, post};
 use axum::Router;
+use util::metrics::metrics_handler;
 
 pub async fn route_not_found() -> StatusCode {
     StatusCode::NOT_FOUND
 }
 
-pub fn post_routes() -> Router<AppState> {
+/// 存活和就绪检查，供负载均衡器和容器编排使用；Prometheus 指标
+pub fn health_routes() -> Router<AppState> {
     Router::new()
-        .route("/api/post/search_posts", get(search_posts))
-        .route("/api/post/get_single_post/:id", get(get_single_post))
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+        .route("/metrics", get(metrics_handler))
+}
+
+/// 缓存命中时仍需记录文章浏览，所以需要 state
+pub fn post_routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/post/search_posts",
+            get(search_posts)
+                .layer(middleware::from_fn_with_state(cache::POSTS, cache_response))
+                .layer(middleware::from_fn_with_state("search_posts", rate_limit)),
+        )
+        .route(
+            "/api/post/get_single_post/:id",
+            get(get_single_post)
+                .layer(middleware::from_fn_with_state(
+                    cache::POSTS,
+                    revalidate_response,
+                ))
+                .layer(middleware::from_fn_with_state(state, record_cached_view)),
+        )
 }
 
 pub fn tag_routes() -> Router<AppState> {
     Router::new()
-        .route("/api/tag/search_tags", get(search_tags))
-        .route("/api/tag/get_tag_info/:id", get(get_tag_info))
+        .route(
+            "/api/tag/search_tags",
+            get(search_tags).layer(middleware::from_fn_with_state(cache::TAGS, cache_response)),
+        )
+        .route(
+            "/api/tag/get_tag_info/:id",
+            get(get_tag_info).layer(middleware::from_fn_with_state(cache::TAGS, cache_response)),
+        )
+}
+
+/// RSS 与 Atom 订阅源
+pub fn feed_routes() -> Router<AppState> {
+    Router::new()
+        .route("/feed.xml", get(rss_feed))
+        .route("/atom.xml", get(atom_feed))
+        .route("/tag/:id/feed.xml", get(tag_rss_feed))
+        .route("/tag/:id/atom.xml", get(tag_atom_feed))
+}
+
+/// 站点地图与 robots.txt
+pub fn sitemap_routes() -> Router<AppState> {
+    Router::new()
+        .route("/sitemap.xml", get(sitemap))
+        .route("/sitemap/tags.xml", get(tag_sitemap))
+        .route("/sitemap/posts/:file", get(post_sitemap))
+        .route("/robots.txt", get(robots))
 }
 
 pub fn comment_routes() -> Router<AppState> {
     Router::new()
-        .route("/api/comment/new_comment", post(new_comment))
+        .route(
+            "/api/comment/new_comment",
+            post(new_comment)
+                .layer(middleware::from_fn_with_state(
+                    cache::COMMENT_WRITES,
+                    invalidate_cache,
+                ))
+                .layer(middleware::from_fn_with_state("new_comment", rate_limit)),
+        )
         .route(
             "/api/comment/get_post_comments/:post_id",
-            get(get_post_comments),
+            get(get_post_comments).layer(middleware::from_fn_with_state(
+                cache::COMMENTS,
+                revalidate_response,
+            )),
+        )
+}
+
+/// 后台管理接口，除登录外均需要携带 token
+/// 登录校验需要查询管理员是否被禁用，所以需要 state
+pub fn admin_routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/api/admin/admin/new_admin", post(new_admin))
+        .route("/api/admin/admin/search_admins", get(search_admins))
+        .route("/api/admin/admin/edit_admin", post(edit_admin))
+        .route("/api/admin/admin/toggle_admin/:id", post(toggle_admin))
+        .route("/api/admin/admin/get_admin_info/:id", get(get_admin_info))
+        .route(
+            "/api/admin/post/new_post",
+            post(new_post).layer(middleware::from_fn_with_state(
+                cache::POST_WRITES,
+                invalidate_cache,
+            )),
+        )
+        .route(
+            "/api/admin/post/edit_post",
+            post(edit_post).layer(middleware::from_fn_with_state(
+                cache::POST_WRITES,
+                invalidate_cache,
+            )),
+        )
+        .route(
+            "/api/admin/post/toggle_post/:id",
+            post(toggle_post).layer(middleware::from_fn_with_state(
+                cache::POST_WRITES,
+                invalidate_cache,
+            )),
+        )
+        .route("/api/admin/post/search_posts", get(admin_search_posts))
+        .route("/api/admin/post/get_post/:id", get(admin_get_post))
+        .route("/api/admin/post/list_revisions/:id", get(list_revisions))
+        .route("/api/admin/post/diff_revisions", get(diff_revisions))
+        .route(
+            "/api/admin/post/restore_revision",
+            post(restore_revision).layer(middleware::from_fn_with_state(
+                cache::POST_WRITES,
+                invalidate_cache,
+            )),
+        )
+        .route(
+            "/api/admin/tag/new_tag",
+            post(new_tag).layer(middleware::from_fn_with_state(
+                cache::POST_WRITES,
+                invalidate_cache,
+            )),
+        )
+        .route(
+            "/api/admin/tag/edit_tag",
+            post(edit_tag).layer(middleware::from_fn_with_state(
+                cache::POST_WRITES,
+                invalidate_cache,
+            )),
+        )
+        .route(
+            "/api/admin/tag/toggle_tag/:id",
+            post(toggle_tag).layer(middleware::from_fn_with_state(
+                cache::POST_WRITES,
+                invalidate_cache,
+            )),
+        )
+        .route("/api/admin/tag/search_tags", get(admin_search_tags))
+        .route(
+            "/api/admin/comment/toggle_comment/:id",
+            post(toggle_comment).layer(middleware::from_fn_with_state(
+                cache::COMMENT_WRITES,
+                invalidate_cache,
+            )),
+        )
+        .route("/api/admin/comment/list_comments", get(list_comments))
+        .route(
+            "/api/admin/comment/set_comments_status",
+            post(set_comments_status).layer(middleware::from_fn_with_state(
+                cache::COMMENT_WRITES,
+                invalidate_cache,
+            )),
+        )
+        .route("/api/admin/stats/daily_views", get(daily_views))
+        .route("/api/admin/stats/daily_comments", get(daily_comments))
+        .route("/api/admin/stats/top_posts", get(top_posts))
+        .route("/api/admin/stats/tag_posts", get(tag_posts))
+        .route_layer(middleware::from_fn_with_state(state, require_auth))
+        .route(
+            "/api/admin/login",
+            post(login).layer(middleware::from_fn_with_state("login", rate_limit)),
         )
 }
@@ -1,17 +1,30 @@
+use std::collections::HashMap;
+
 use anyhow::{anyhow, Result};
 use entity::entity::comment::{self, Column};
-use sea_orm::prelude::Expr;
-use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use entity::entity::sea_orm_active_enums::CommentStatus;
+use sea_orm::prelude::{DateTimeWithTimeZone, Expr};
+use sea_orm::sqlx::types::chrono::NaiveDate;
+use sea_orm::{
+    ActiveEnum, ColumnTrait, Condition, DatabaseConnection, EntityTrait, Order, PaginatorTrait,
+    QueryFilter, QueryOrder, QuerySelect, QueryTrait, Select, Set,
+};
+use tracing::instrument;
 
+#[instrument(skip_all)]
 pub async fn insert_comment(
     db: &DatabaseConnection,
     post_id: i32,
     name: String,
     hashed_email: String,
     content: String,
+    parent_id: Option<i32>,
+    status: CommentStatus,
 ) -> Result<i32> {
     let new_comment = comment::ActiveModel {
         post_id: Set(post_id),
+        parent_id: Set(parent_id),
+        status: Set(status),
         name: Set(name),
         hashed_email: Set(Some(hashed_email)),
         content: Set(Some(content)),
@@ -21,14 +34,93 @@ pub async fn insert_comment(
     Ok(res.last_insert_id)
 }
 
-pub async fn select_comments(db: &DatabaseConnection, post_id: i32) -> Result<Vec<comment::Model>> {
-    let res = comment::Entity::find()
+/// 文章下显示的评论：未删除且已通过审核
+fn visible_comments(post_id: i32) -> Select<comment::Entity> {
+    comment::Entity::find()
         .filter(Column::PostId.eq(post_id))
-        .filter(Column::IsDel.eq(false)) // 默认查询未被删除的评论
+        .filter(Column::IsDel.eq(false))
+        .filter(Column::Status.eq(CommentStatus::Approved))
+}
+
+/// 顶层评论：没有父评论，或者父评论已被删除、未通过审核
+fn root_comments(post_id: i32) -> Select<comment::Entity> {
+    let visible_ids = visible_comments(post_id)
+        .select_only()
+        .column(Column::Id)
+        .into_query();
+    visible_comments(post_id).filter(
+        Condition::any()
+            .add(Column::ParentId.is_null())
+            .add(Column::ParentId.not_in_subquery(visible_ids)),
+    )
+}
+
+/// select_comment_count 文章下显示的评论总数和顶层评论数
+#[instrument(skip_all)]
+pub async fn select_comment_count(db: &DatabaseConnection, post_id: i32) -> Result<(u64, u64)> {
+    let total = visible_comments(post_id).count(db).await?;
+    let roots = root_comments(post_id).count(db).await?;
+    Ok((total, roots))
+}
+
+/// select_root_comments 按发表时间分页查询顶层评论，after 为上一页最后一条的 (created_at, id)
+#[instrument(skip_all)]
+pub async fn select_root_comments(
+    db: &DatabaseConnection,
+    post_id: i32,
+    newest: bool,
+    after: Option<(DateTimeWithTimeZone, i32)>,
+    limit: u64,
+    offset: u64,
+) -> Result<Vec<comment::Model>> {
+    let mut select = root_comments(post_id);
+    if let Some((created_at, id)) = after {
+        let key = Expr::tuple([
+            Expr::col((comment::Entity, Column::CreatedAt)).into(),
+            Expr::col((comment::Entity, Column::Id)).into(),
+        ]);
+        let after = Expr::tuple([Expr::val(created_at).into(), Expr::val(id).into()]);
+        select = select.filter(if newest { key.lt(after) } else { key.gt(after) });
+    }
+    let order = if newest { Order::Desc } else { Order::Asc };
+    let res = select
+        .order_by(Column::CreatedAt, order.clone())
+        .order_by(Column::Id, order)
+        .limit(Some(limit))
+        .offset(Some(offset))
         .all(db)
         .await?;
     Ok(res)
 }
+
+/// select_replies 逐层查询这些评论下显示的全部回复，每层按时间从旧到新
+#[instrument(skip_all)]
+pub async fn select_replies(
+    db: &DatabaseConnection,
+    post_id: i32,
+    ids: Vec<i32>,
+) -> Result<Vec<comment::Model>> {
+    let mut res = vec![];
+    let mut parents = ids;
+    while !parents.is_empty() {
+        let replies = visible_comments(post_id)
+            .filter(Column::ParentId.is_in(parents))
+            .order_by_asc(Column::CreatedAt)
+            .order_by_asc(Column::Id)
+            .all(db)
+            .await?;
+        parents = replies.iter().map(|reply| reply.id).collect();
+        res.extend(replies);
+    }
+    Ok(res)
+}
+
+#[instrument(skip_all)]
+pub async fn select_a_comment(db: &DatabaseConnection, id: i32) -> Result<Option<comment::Model>> {
+    Ok(comment::Entity::find_by_id(id).one(db).await?)
+}
+
+#[instrument(skip_all)]
 pub async fn update_comment_del(db: &DatabaseConnection, id: i32) -> Result<bool> {
     let res = comment::Entity::update_many()
         .filter(Column::Id.eq(id))
@@ -41,3 +133,98 @@ pub async fn update_comment_del(db: &DatabaseConnection, id: i32) -> Result<bool
         None => Err(anyhow!("No such comment")),
     }
 }
+
+fn filter_comments(status: Option<CommentStatus>, is_del: Option<bool>) -> Select<comment::Entity> {
+    let mut select = comment::Entity::find();
+    if let Some(status) = status {
+        select = select.filter(Column::Status.eq(status));
+    }
+    if let Some(is_del) = is_del {
+        select = select.filter(Column::IsDel.eq(is_del));
+    }
+    select
+}
+
+#[instrument(skip_all)]
+pub async fn select_comment_total(
+    db: &DatabaseConnection,
+    status: Option<CommentStatus>,
+    is_del: Option<bool>,
+) -> Result<u64> {
+    Ok(filter_comments(status, is_del).count(db).await?)
+}
+
+/// select_comments_by_status 查询所有文章的评论，从新到旧
+#[instrument(skip_all)]
+pub async fn select_comments_by_status(
+    db: &DatabaseConnection,
+    status: Option<CommentStatus>,
+    is_del: Option<bool>,
+    page_size: i32,
+    offset: u64,
+) -> Result<Vec<comment::Model>> {
+    let res = filter_comments(status, is_del)
+        .order_by_desc(Column::CreatedAt)
+        .order_by_desc(Column::Id)
+        .limit(Some(page_size as u64))
+        .offset(Some(offset))
+        .all(db)
+        .await?;
+    Ok(res)
+}
+
+/// update_comments_status 批量修改审核状态，返回实际修改了的评论 id
+#[instrument(skip_all)]
+pub async fn update_comments_status(
+    db: &DatabaseConnection,
+    ids: Vec<i32>,
+    status: CommentStatus,
+) -> Result<Vec<i32>> {
+    let res = comment::Entity::update_many()
+        .filter(Column::Id.is_in(ids))
+        .col_expr(
+            Column::Status,
+            Expr::val(status).as_enum(CommentStatus::name()),
+        )
+        .exec_with_returning(db)
+        .await?;
+    Ok(res.into_iter().map(|row| row.id).collect())
+}
+
+/// select_moderated_comments 最近审核过的正常评论和垃圾评论，用于训练垃圾评论分类器
+#[instrument(skip_all)]
+pub async fn select_moderated_comments(
+    db: &DatabaseConnection,
+    limit: u64,
+) -> Result<Vec<comment::Model>> {
+    let res = comment::Entity::find()
+        .filter(Column::Status.is_in([CommentStatus::Approved, CommentStatus::Spam]))
+        .order_by_desc(Column::CreatedAt)
+        .limit(Some(limit))
+        .all(db)
+        .await?;
+    Ok(res)
+}
+
+/// select_daily_comments 区间内每天新增的未删除评论数，post_id 为 None 时统计全站
+#[instrument(skip_all)]
+pub async fn select_daily_comments(
+    db: &DatabaseConnection,
+    post_id: Option<i32>,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<HashMap<NaiveDate, i64>> {
+    let day = Expr::cust(r#"DATE("comment"."created_at")"#);
+    let mut select = comment::Entity::find()
+        .select_only()
+        .column_as(day.clone(), "day")
+        .column_as(Expr::col(Column::Id).count(), "comments")
+        .filter(Column::IsDel.eq(false))
+        .filter(Expr::expr(day.clone()).between(start, end))
+        .group_by(day);
+    if let Some(post_id) = post_id {
+        select = select.filter(Column::PostId.eq(post_id));
+    }
+    let res = select.into_tuple::<(NaiveDate, i64)>().all(db).await?;
+    Ok(res.into_iter().collect())
+}
@@ -1,16 +1,24 @@
+use std::sync::Arc;
+
 use proto::comment_service_server::CommentServiceServer;
-use util::{get_db_connection, get_service_addr};
+use spam::{BayesChecker, SpamFilter};
+use util::health::health_service;
+use util::interceptor::CheckServiceToken;
+use util::metrics::serve_metrics;
+use util::{
+    apply_pending_migrations, get_db_connection, get_service_addr, grpc_server, shutdown_signal,
+};
 
 mod dbaccess;
 mod server;
+mod spam;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    tracing_subscriber::fmt()
-        .with_target(false)
-        .compact()
-        .init();
-
+    let config = util::telemetry::init(util::Service::Comment).map_err(|err| {
+        tracing::error!("{}", err);
+        err
+    })?;
     let addr = get_service_addr(util::Service::Comment).map_err(|err| {
         tracing::error!("{}", err);
         err
@@ -19,18 +27,60 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         tracing::error!("{}", err);
         err
     })?;
+    apply_pending_migrations(&db).await.map_err(|err| {
+        tracing::error!("{}", err);
+        err
+    })?;
+    let auth = CheckServiceToken::from_config().map_err(|err| {
+        tracing::error!("{}", err);
+        err
+    })?;
 
-    let comment_service = server::Comment::new(db);
+    let classifier = Arc::new(BayesChecker::new(config.spam.bayes_threshold));
+    let spam_filter = SpamFilter::from_config(&config.spam, classifier.clone()).map_err(|err| {
+        tracing::error!("{}", err);
+        err
+    })?;
+
+    let health = health_service::<CommentServiceServer<server::Comment>>(
+        db.clone(),
+        config.database.health_check_interval(),
+    );
+    let comment_service = server::Comment::new(
+        db,
+        config.feature(util::Service::Comment, "require_approval"),
+        spam_filter,
+        classifier,
+    );
+    comment_service.train_classifier().await.map_err(|err| {
+        tracing::error!("{}", err);
+        err
+    })?;
+    comment_service.spawn_training();
+
+    serve_metrics(util::Service::Comment).await.map_err(|err| {
+        tracing::error!("{}", err);
+        err
+    })?;
+    let mut grpc = grpc_server(util::Service::Comment).map_err(|err| {
+        tracing::error!("{}", err);
+        err
+    })?;
 
     tracing::info!("Comment Service runs at: {}", addr);
-    tonic::transport::Server::builder()
-        .add_service(CommentServiceServer::new(comment_service))
-        .serve(addr.parse()?)
+    grpc
+        .add_service(health)
+        .add_service(CommentServiceServer::with_interceptor(
+            comment_service,
+            auth,
+        ))
+        .serve_with_shutdown(addr.parse()?, shutdown_signal())
         .await
         .map_err(|err| {
             tracing::error!("{}", err);
             err
         })?;
 
+    util::telemetry::shutdown().await;
     Ok(())
 }
@@ -1,24 +1,88 @@
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
+use entity::entity::comment;
+use entity::entity::sea_orm_active_enums::CommentStatus;
 use proto::{
     comment_service_server::CommentService, CreateCommentReply, CreateCommentRequest,
-    GetPostCommentsReply, GetPostCommentsRequest, ToggleCommentReply, ToggleCommentRequest,
+    DailyComments, GetDailyCommentsReply, GetDailyCommentsRequest, GetPostCommentsReply,
+    GetPostCommentsRequest, ListCommentsReply, ListCommentsRequest, SetCommentsStatusReply,
+    SetCommentsStatusRequest, ToggleCommentReply, ToggleCommentRequest,
 };
 use sea_orm::DatabaseConnection;
+use tokio::sync::Notify;
 use tonic::{Request, Response, Status};
+use util::cursor::Cursor;
+use util::stats::{date_range, fill_days};
 
 use crate::dbaccess::*;
+use crate::spam::{BayesChecker, NewComment, SpamFilter, Verdict};
+
+const PAGE_SIZE: i32 = 10;
+const POST_COMMENTS_PAGE_SIZE: i32 = 20; // 文章评论每页的顶层评论数
+const MAX_PAGE_SIZE: i32 = 100;
+const TRAINING_LIMIT: u64 = 5000; // 训练分类器使用的最近审核过的评论数
+const TRAINING_DELAY: Duration = Duration::from_secs(30); // 审核后等待这么久再重新训练，期间的多次审核只训练一次
 
 pub struct Comment {
     db_conn: Arc<DatabaseConnection>,
+    require_approval: bool, // 新评论是否需要审核后才显示
+    spam_filter: SpamFilter,
+    classifier: Arc<BayesChecker>, // 同时也在 spam_filter 中
+    retrain: Arc<Notify>,          // 审核结果变化时通知后台重新训练
 }
 
 impl Comment {
-    pub fn new(db_conn: DatabaseConnection) -> Self {
+    pub fn new(
+        db_conn: DatabaseConnection,
+        require_approval: bool,
+        spam_filter: SpamFilter,
+        classifier: Arc<BayesChecker>,
+    ) -> Self {
         Comment {
             db_conn: Arc::new(db_conn),
+            require_approval,
+            spam_filter,
+            classifier,
+            retrain: Arc::new(Notify::new()),
         }
     }
+
+    /// train_classifier 用审核过的评论重新训练垃圾评论分类器
+    pub async fn train_classifier(&self) -> anyhow::Result<()> {
+        train_classifier(&self.db_conn, &self.classifier).await
+    }
+
+    /// spawn_training 在后台重新训练，审核接口只发出通知，不等待训练完成
+    pub fn spawn_training(&self) {
+        let db_conn = self.db_conn.clone();
+        let classifier = self.classifier.clone();
+        let retrain = self.retrain.clone();
+        tokio::spawn(async move {
+            loop {
+                retrain.notified().await;
+                tokio::time::sleep(TRAINING_DELAY).await;
+                if let Err(err) = train_classifier(&db_conn, &classifier).await {
+                    tracing::error!("Failed to train spam classifier: {}", err);
+                }
+            }
+        });
+    }
+}
+
+async fn train_classifier(
+    db: &DatabaseConnection,
+    classifier: &BayesChecker,
+) -> anyhow::Result<()> {
+    let comments = select_moderated_comments(db, TRAINING_LIMIT).await?;
+    classifier.train(comments.iter().map(|comment| {
+        (
+            comment.content.as_deref().unwrap_or_default(),
+            comment.status == CommentStatus::Spam,
+        )
+    }));
+    Ok(())
 }
 
 #[tonic::async_trait]
@@ -32,35 +96,143 @@ impl CommentService for Comment {
             name,
             hashed_email,
             content,
+            parent_id,
         } = request.into_inner();
-        let id = insert_comment(&self.db_conn, post_id, name, hashed_email, content)
-            .await
-            .map_err(|err| Status::internal(err.to_string()))?;
+        // 去掉首尾空白后再检查和保存，检查的长度与写入的内容一致
+        let name = name.trim().to_owned();
+        let content = content.trim().to_owned();
+        let verdict = self
+            .spam_filter
+            .check(&NewComment {
+                name: &name,
+                content: &content,
+            })
+            .await;
+        let status = match verdict {
+            Verdict::Reject(reason) => return Err(Status::invalid_argument(reason)),
+            Verdict::Spam(reason) => {
+                tracing::info!("Comment on post {} marked as spam: {}", post_id, reason);
+                CommentStatus::Spam
+            }
+            Verdict::Ham if self.require_approval => CommentStatus::Pending,
+            Verdict::Ham => CommentStatus::Approved,
+        };
+        if let Some(parent_id) = parent_id {
+            let parent = select_a_comment(&self.db_conn, parent_id)
+                .await
+                .map_err(|err| Status::internal(err.to_string()))?;
+            match parent {
+                Some(parent)
+                    if parent.post_id == post_id
+                        && !parent.is_del
+                        && parent.status == CommentStatus::Approved => {}
+                _ => {
+                    return Err(Status::invalid_argument(
+                        "parent comment does not belong to the post",
+                    ))
+                }
+            }
+        }
+        let id = insert_comment(
+            &self.db_conn,
+            post_id,
+            name,
+            hashed_email,
+            content,
+            parent_id,
+            status.clone(),
+        )
+        .await
+        .map_err(|err| Status::internal(err.to_string()))?;
 
-        Ok(Response::new(CreateCommentReply { id }))
+        Ok(Response::new(CreateCommentReply {
+            id,
+            status: status_to_proto(&status) as i32,
+        }))
     }
 
     async fn get_post_comments(
         &self,
         request: Request<GetPostCommentsRequest>,
     ) -> Result<Response<GetPostCommentsReply>, Status> {
-        let GetPostCommentsRequest { post_id } = request.into_inner();
-        let res = select_comments(&self.db_conn, post_id)
+        let GetPostCommentsRequest {
+            post_id,
+            page,
+            cursor,
+            page_size,
+            sort,
+        } = request.into_inner();
+        let page_size = page_size.unwrap_or(POST_COMMENTS_PAGE_SIZE);
+        if page_size <= 0 || page_size > MAX_PAGE_SIZE {
+            return Err(Status::invalid_argument("page_size out of range"));
+        }
+        let newest = match sort.map(proto::CommentSort::try_from) {
+            None => false,
+            Some(Ok(sort)) => sort == proto::CommentSort::Newest,
+            Some(Err(_)) => return Err(Status::invalid_argument("unknown comment sort")),
+        };
+        let (page, after, offset) = match cursor {
+            Some(cursor) => match Cursor::decode(&cursor) {
+                Some(Cursor::After {
+                    newest: cursor_newest,
+                    time,
+                    id,
+                }) if cursor_newest == newest => (0, Some((time, id)), 0),
+                Some(_) => return Err(Status::invalid_argument("cursor does not match sort")),
+                None => return Err(Status::invalid_argument("invalid cursor")),
+            },
+            None => {
+                let page = page.unwrap_or(0).max(0);
+                (page, None, page as u64 * page_size as u64)
+            }
+        };
+
+        let (total, root_total) = select_comment_count(&self.db_conn, post_id)
             .await
             .map_err(|err| Status::internal(err.to_string()))?;
-        let comments = res
-            .iter()
-            .map(|comment| proto::Comment {
-                id: comment.id,
-                post_id: comment.post_id,
-                name: comment.name.to_owned(),
-                hashed_email: comment.hashed_email.to_owned().unwrap_or_default(),
-                content: comment.content.to_owned().unwrap_or_default(),
-                created_at: util::datetime_conversion(Some(comment.created_at)),
-                is_del: comment.is_del,
+        let page_total = f64::ceil(root_total as f64 / page_size as f64) as i32;
+        // 多查一条用于判断是否还有下一页
+        let mut roots = select_root_comments(
+            &self.db_conn,
+            post_id,
+            newest,
+            after,
+            page_size as u64 + 1,
+            offset,
+        )
+        .await
+        .map_err(|err| Status::internal(err.to_string()))?;
+        let next_cursor = if roots.len() > page_size as usize {
+            roots.truncate(page_size as usize);
+            roots.last().map(|root| {
+                Cursor::After {
+                    newest,
+                    time: root.created_at,
+                    id: root.id,
+                }
+                .encode()
             })
+        } else {
+            None
+        };
+        let replies = select_replies(
+            &self.db_conn,
+            post_id,
+            roots.iter().map(|root| root.id).collect(),
+        )
+        .await
+        .map_err(|err| Status::internal(err.to_string()))?;
+        let comments = thread_order(roots, replies)
+            .into_iter()
+            .map(|(comment, depth)| model_to_comment(comment, depth))
             .collect::<Vec<proto::Comment>>();
-        Ok(Response::new(GetPostCommentsReply { comments }))
+        Ok(Response::new(GetPostCommentsReply {
+            comments,
+            total: total as i64,
+            page,
+            page_total,
+            next_cursor,
+        }))
     }
 
     async fn toggle_comment(
@@ -73,4 +245,133 @@ impl CommentService for Comment {
             .map_err(|err| Status::internal(err.to_string()))?;
         Ok(Response::new(ToggleCommentReply { id, is_del: res }))
     }
+
+    async fn list_comments(
+        &self,
+        request: Request<ListCommentsRequest>,
+    ) -> Result<Response<ListCommentsReply>, Status> {
+        let ListCommentsRequest {
+            status,
+            page,
+            is_del,
+        } = request.into_inner();
+        let status = status.map(status_from_proto).transpose()?;
+        let page = page.unwrap_or(0).max(0);
+        let offset = page as u64 * PAGE_SIZE as u64;
+        let record_total = select_comment_total(&self.db_conn, status.clone(), is_del)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+        let page_total = f64::ceil(record_total as f64 / PAGE_SIZE as f64) as i32;
+        let res = select_comments_by_status(&self.db_conn, status, is_del, PAGE_SIZE, offset)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+        let comments = res
+            .into_iter()
+            .map(|comment| model_to_comment(comment, 0))
+            .collect::<Vec<proto::Comment>>();
+        Ok(Response::new(ListCommentsReply {
+            page,
+            page_total,
+            comments,
+        }))
+    }
+
+    async fn set_comments_status(
+        &self,
+        request: Request<SetCommentsStatusRequest>,
+    ) -> Result<Response<SetCommentsStatusReply>, Status> {
+        let SetCommentsStatusRequest { ids, status } = request.into_inner();
+        let status = status_from_proto(status)?;
+        if ids.is_empty() {
+            return Ok(Response::new(SetCommentsStatusReply { ids }));
+        }
+        let ids = update_comments_status(&self.db_conn, ids, status)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+        // 审核结果变化后在后台重新训练
+        self.retrain.notify_one();
+        Ok(Response::new(SetCommentsStatusReply { ids }))
+    }
+
+    async fn get_daily_comments(
+        &self,
+        request: Request<GetDailyCommentsRequest>,
+    ) -> Result<Response<GetDailyCommentsReply>, Status> {
+        let GetDailyCommentsRequest {
+            post_id,
+            start,
+            end,
+        } = request.into_inner();
+        let (start, end) = date_range(&start, &end).map_err(Status::invalid_argument)?;
+        let res = select_daily_comments(&self.db_conn, post_id, start, end)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+        let days = fill_days(start, end, &res)
+            .into_iter()
+            .map(|(day, comments)| DailyComments { day, comments })
+            .collect::<Vec<DailyComments>>();
+        Ok(Response::new(GetDailyCommentsReply { days }))
+    }
+}
+
+/// depth 为评论在回复树中的层级，不按回复树排列时为 0
+fn model_to_comment(comment: comment::Model, depth: i32) -> proto::Comment {
+    proto::Comment {
+        id: comment.id,
+        post_id: comment.post_id,
+        name: comment.name,
+        hashed_email: comment.hashed_email.unwrap_or_default(),
+        content: comment.content.unwrap_or_default(),
+        created_at: util::datetime_conversion(Some(comment.created_at)),
+        is_del: comment.is_del,
+        parent_id: comment.parent_id,
+        depth,
+        status: status_to_proto(&comment.status) as i32,
+    }
+}
+
+#[allow(clippy::result_large_err)]
+fn status_from_proto(status: i32) -> Result<CommentStatus, Status> {
+    let status = proto::CommentStatus::try_from(status)
+        .map_err(|_| Status::invalid_argument("unknown comment status"))?;
+    Ok(match status {
+        proto::CommentStatus::Approved => CommentStatus::Approved,
+        proto::CommentStatus::Pending => CommentStatus::Pending,
+        proto::CommentStatus::Spam => CommentStatus::Spam,
+        proto::CommentStatus::Rejected => CommentStatus::Rejected,
+    })
+}
+
+fn status_to_proto(status: &CommentStatus) -> proto::CommentStatus {
+    match status {
+        CommentStatus::Approved => proto::CommentStatus::Approved,
+        CommentStatus::Pending => proto::CommentStatus::Pending,
+        CommentStatus::Spam => proto::CommentStatus::Spam,
+        CommentStatus::Rejected => proto::CommentStatus::Rejected,
+    }
+}
+
+/// thread_order 把顶层评论和它们的回复排成回复树的深度优先顺序，同时返回层级。
+/// 顶层评论保持传入的顺序，replies 需按时间排序
+fn thread_order(
+    roots: Vec<comment::Model>,
+    replies: Vec<comment::Model>,
+) -> Vec<(comment::Model, i32)> {
+    let mut children: HashMap<i32, Vec<comment::Model>> = HashMap::new();
+    for reply in replies {
+        if let Some(parent_id) = reply.parent_id {
+            children.entry(parent_id).or_default().push(reply);
+        }
+    }
+
+    let mut res = vec![];
+    // 栈中逆序存放，保证同一层按顺序弹出
+    let mut stack = roots.into_iter().rev().map(|c| (c, 0)).collect::<Vec<_>>();
+    while let Some((comment, depth)) = stack.pop() {
+        if let Some(replies) = children.remove(&comment.id) {
+            stack.extend(replies.into_iter().rev().map(|c| (c, depth + 1)));
+        }
+        res.push((comment, depth));
+    }
+    res
 }
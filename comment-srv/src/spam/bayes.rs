@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use super::{NewComment, SpamChecker, Verdict};
+
+const MIN_SAMPLES: usize = 10; // 正常评论和垃圾评论都至少有这么多条时才进行判断
+const MAX_TOKENS: usize = 200; // 每条评论最多使用的词数
+
+/// BayesChecker 朴素贝叶斯分类器，用审核过的评论训练，垃圾评论的概率超过阈值时标记为垃圾评论
+pub struct BayesChecker {
+    model: RwLock<BayesModel>,
+    threshold: f64,
+}
+
+#[derive(Default)]
+struct BayesModel {
+    ham_docs: usize,
+    spam_docs: usize,
+    ham_tokens: usize,
+    spam_tokens: usize,
+    counts: HashMap<String, (usize, usize)>, // 词 -> (正常评论中出现次数, 垃圾评论中出现次数)
+}
+
+impl BayesChecker {
+    pub fn new(threshold: f64) -> Self {
+        Self {
+            model: RwLock::new(BayesModel::default()),
+            threshold,
+        }
+    }
+
+    /// train 用 (内容, 是否为垃圾评论) 重新训练，替换原来的模型
+    pub fn train<'a>(&self, samples: impl IntoIterator<Item = (&'a str, bool)>) {
+        let mut model = BayesModel::default();
+        for (content, is_spam) in samples {
+            let tokens = tokenize(content);
+            if is_spam {
+                model.spam_docs += 1;
+                model.spam_tokens += tokens.len();
+            } else {
+                model.ham_docs += 1;
+                model.ham_tokens += tokens.len();
+            }
+            for token in tokens {
+                let count = model.counts.entry(token).or_default();
+                if is_spam {
+                    count.1 += 1;
+                } else {
+                    count.0 += 1;
+                }
+            }
+        }
+        *self.model.write().unwrap() = model;
+    }
+
+    /// spam_probability 训练样本不足时返回 None
+    fn spam_probability(&self, content: &str) -> Option<f64> {
+        let model = self.model.read().unwrap();
+        if model.ham_docs < MIN_SAMPLES || model.spam_docs < MIN_SAMPLES {
+            return None;
+        }
+        let total_docs = (model.ham_docs + model.spam_docs) as f64;
+        let vocabulary = model.counts.len() as f64;
+        let mut ham = (model.ham_docs as f64 / total_docs).ln();
+        let mut spam = (model.spam_docs as f64 / total_docs).ln();
+        for token in tokenize(content) {
+            // 拉普拉斯平滑，没见过的词对结果没有影响
+            let Some(&(ham_count, spam_count)) = model.counts.get(&token) else {
+                continue;
+            };
+            ham += ((ham_count as f64 + 1.0) / (model.ham_tokens as f64 + vocabulary)).ln();
+            spam += ((spam_count as f64 + 1.0) / (model.spam_tokens as f64 + vocabulary)).ln();
+        }
+        Some(1.0 / (1.0 + (ham - spam).exp()))
+    }
+}
+
+#[tonic::async_trait]
+impl SpamChecker for BayesChecker {
+    async fn check(&self, comment: &NewComment<'_>) -> Verdict {
+        match self.spam_probability(comment.content) {
+            Some(p) if p >= self.threshold => Verdict::Spam(format!("spam probability {:.2}", p)),
+            _ => Verdict::Ham,
+        }
+    }
+}
+
+/// tokenize 英文和数字按单词切分并转为小写，中日韩文字按相邻两个字切分
+fn tokenize(content: &str) -> Vec<String> {
+    let mut tokens = vec![];
+    let mut word = String::new();
+    let mut prev_cjk = None;
+    for c in content.chars() {
+        if is_cjk(c) {
+            if !word.is_empty() {
+                tokens.push(std::mem::take(&mut word));
+            }
+            match prev_cjk {
+                Some(prev) => tokens.push(format!("{}{}", prev, c)),
+                None => tokens.push(c.to_string()),
+            }
+            prev_cjk = Some(c);
+            continue;
+        }
+        prev_cjk = None;
+        if c.is_alphanumeric() {
+            word.extend(c.to_lowercase());
+        } else if !word.is_empty() {
+            tokens.push(std::mem::take(&mut word));
+        }
+    }
+    if !word.is_empty() {
+        tokens.push(word);
+    }
+    tokens.truncate(MAX_TOKENS);
+    tokens
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c, '\u{3040}'..='\u{30ff}' | '\u{3400}'..='\u{9fff}' | '\u{ac00}'..='\u{d7af}' | '\u{f900}'..='\u{faff}')
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spam::bayes::tokenize;
+    use crate::spam::{BayesChecker, NewComment, SpamChecker, Verdict};
+
+    fn comment(content: &str) -> NewComment<'_> {
+        NewComment {
+            name: "TestName1",
+            content,
+        }
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            tokenize("Rust 微服务, OK!"),
+            vec!["rust", "微", "微服", "服务", "ok"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bayes_checker() {
+        let checker = BayesChecker::new(0.9);
+        let spam = "buy cheap pills now, 免费领取优惠";
+        let ham = "thanks for the post, 写得很好";
+        // 样本不足时不做判断
+        checker.train([(spam, true), (ham, false)]);
+        assert_eq!(checker.check(&comment(spam)).await, Verdict::Ham);
+
+        checker.train((0..10).flat_map(|_| [(spam, true), (ham, false)]));
+        assert!(matches!(
+            checker.check(&comment("cheap pills 免费领取")).await,
+            Verdict::Spam(_)
+        ));
+        assert_eq!(checker.check(&comment("很好, thanks")).await, Verdict::Ham);
+    }
+}
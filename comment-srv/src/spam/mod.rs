@@ -0,0 +1,68 @@
+// 新评论的垃圾评论检查，按顺序执行各个 SpamChecker，遇到第一个非 Ham 的结果即停止
+
+mod bayes;
+mod rules;
+
+use std::sync::Arc;
+use util::config::SpamConfig;
+
+pub use bayes::BayesChecker;
+pub use rules::{BlocklistChecker, LengthChecker, LinkChecker};
+
+/// 待检查的新评论
+pub struct NewComment<'a> {
+    pub name: &'a str,
+    pub content: &'a str,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Ham,
+    Spam(String),   // 保存但标记为垃圾评论，不公开显示
+    Reject(String), // 直接拒绝，返回 invalid_argument
+}
+
+#[tonic::async_trait]
+pub trait SpamChecker: Send + Sync {
+    async fn check(&self, comment: &NewComment<'_>) -> Verdict;
+}
+
+pub struct SpamFilter {
+    checkers: Vec<Arc<dyn SpamChecker>>,
+}
+
+impl SpamFilter {
+    pub fn new() -> Self {
+        Self { checkers: vec![] }
+    }
+
+    pub fn with(mut self, checker: Arc<dyn SpamChecker>) -> Self {
+        self.checkers.push(checker);
+        self
+    }
+
+    /// from_config 默认的检查顺序：长度、屏蔽词、链接数量、贝叶斯分类器
+    ///
+    /// 屏蔽词文件每行一个，以 re: 开头的行为正则表达式
+    pub fn from_config(config: &SpamConfig, bayes: Arc<BayesChecker>) -> anyhow::Result<Self> {
+        let blocklist = match &config.blocklist_file {
+            Some(path) => BlocklistChecker::parse(&std::fs::read_to_string(path)?)?,
+            None => BlocklistChecker::default(),
+        };
+        Ok(Self::new()
+            .with(Arc::new(LengthChecker::default()))
+            .with(Arc::new(blocklist))
+            .with(Arc::new(LinkChecker::new(config.max_links)))
+            .with(bayes))
+    }
+
+    pub async fn check(&self, comment: &NewComment<'_>) -> Verdict {
+        for checker in &self.checkers {
+            let verdict = checker.check(comment).await;
+            if verdict != Verdict::Ham {
+                return verdict;
+            }
+        }
+        Verdict::Ham
+    }
+}
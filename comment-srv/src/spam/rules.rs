@@ -0,0 +1,180 @@
+use regex::Regex;
+
+use super::{NewComment, SpamChecker, Verdict};
+
+const MAX_CONTENT_LEN: usize = 200; // 与 comment.content 的 varchar(200) 一致
+const MAX_NAME_LEN: usize = 255;
+
+/// LengthChecker 内容和昵称的长度，超出数据库字段长度的直接拒绝
+pub struct LengthChecker {
+    max_content_len: usize,
+    max_name_len: usize,
+}
+
+impl Default for LengthChecker {
+    fn default() -> Self {
+        Self {
+            max_content_len: MAX_CONTENT_LEN,
+            max_name_len: MAX_NAME_LEN,
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl SpamChecker for LengthChecker {
+    async fn check(&self, comment: &NewComment<'_>) -> Verdict {
+        // 按写入数据库的原样计算长度，空白也占用字段长度
+        let content_len = comment.content.chars().count();
+        let name_len = comment.name.chars().count();
+        if comment.content.trim().is_empty() {
+            Verdict::Reject("content is empty".into())
+        } else if content_len > self.max_content_len {
+            Verdict::Reject(format!(
+                "content is longer than {} characters",
+                self.max_content_len
+            ))
+        } else if comment.name.trim().is_empty() {
+            Verdict::Reject("name is empty".into())
+        } else if name_len > self.max_name_len {
+            Verdict::Reject(format!(
+                "name is longer than {} characters",
+                self.max_name_len
+            ))
+        } else {
+            Verdict::Ham
+        }
+    }
+}
+
+/// LinkChecker 链接过多的评论标记为垃圾评论
+pub struct LinkChecker {
+    max_links: usize,
+}
+
+impl LinkChecker {
+    pub fn new(max_links: usize) -> Self {
+        Self { max_links }
+    }
+}
+
+#[tonic::async_trait]
+impl SpamChecker for LinkChecker {
+    async fn check(&self, comment: &NewComment<'_>) -> Verdict {
+        let content = comment.content.to_lowercase();
+        let links = content.matches("http://").count()
+            + content.matches("https://").count()
+            + content.matches("www.").count()
+            - content.matches("://www.").count(); // https://www. 只算一个
+        if links > self.max_links {
+            Verdict::Spam(format!("more than {} links", self.max_links))
+        } else {
+            Verdict::Ham
+        }
+    }
+}
+
+/// BlocklistChecker 包含屏蔽词或匹配正则表达式的评论标记为垃圾评论
+#[derive(Default)]
+pub struct BlocklistChecker {
+    keywords: Vec<String>,
+    patterns: Vec<Regex>,
+}
+
+impl BlocklistChecker {
+    /// parse 每行一个屏蔽词，以 re: 开头的为正则表达式，忽略空行和 # 开头的注释
+    pub fn parse(text: &str) -> Result<Self, regex::Error> {
+        let mut res = Self::default();
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line.strip_prefix("re:") {
+                Some(pattern) => res.patterns.push(Regex::new(pattern.trim())?),
+                None => res.keywords.push(line.to_lowercase()),
+            }
+        }
+        Ok(res)
+    }
+}
+
+#[tonic::async_trait]
+impl SpamChecker for BlocklistChecker {
+    async fn check(&self, comment: &NewComment<'_>) -> Verdict {
+        let text = format!("{}\n{}", comment.name, comment.content);
+        let lowercase = text.to_lowercase();
+        if let Some(keyword) = self
+            .keywords
+            .iter()
+            .find(|k| lowercase.contains(k.as_str()))
+        {
+            return Verdict::Spam(format!("blocked keyword: {}", keyword));
+        }
+        if let Some(pattern) = self.patterns.iter().find(|p| p.is_match(&text)) {
+            return Verdict::Spam(format!("blocked pattern: {}", pattern));
+        }
+        Verdict::Ham
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spam::{
+        BlocklistChecker, LengthChecker, LinkChecker, NewComment, SpamChecker, Verdict,
+    };
+
+    fn comment(content: &str) -> NewComment<'_> {
+        NewComment {
+            name: "TestName1",
+            content,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_length_checker() {
+        let checker = LengthChecker::default();
+        assert_eq!(
+            checker.check(&comment("好".repeat(200).as_str())).await,
+            Verdict::Ham
+        );
+        assert!(matches!(
+            checker.check(&comment("好".repeat(201).as_str())).await,
+            Verdict::Reject(_)
+        ));
+        assert!(matches!(
+            checker.check(&comment("  ")).await,
+            Verdict::Reject(_)
+        ));
+        // 首尾空白同样计入长度
+        let padded = format!(" {} ", "好".repeat(199));
+        assert!(matches!(
+            checker.check(&comment(&padded)).await,
+            Verdict::Reject(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_link_checker() {
+        let checker = LinkChecker::new(2);
+        let two = "https://www.example.com http://example.com";
+        assert_eq!(checker.check(&comment(two)).await, Verdict::Ham);
+        let three = "https://a.com https://b.com www.c.com";
+        assert!(matches!(
+            checker.check(&comment(three)).await,
+            Verdict::Spam(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_blocklist_checker() {
+        let checker = BlocklistChecker::parse("# 注释\ncasino\nre:(?i)cheap\\s+pills\n").unwrap();
+        assert!(matches!(
+            checker.check(&comment("Best CASINO")).await,
+            Verdict::Spam(_)
+        ));
+        assert!(matches!(
+            checker.check(&comment("cheap  Pills")).await,
+            Verdict::Spam(_)
+        ));
+        assert_eq!(checker.check(&comment("nice post")).await, Verdict::Ham);
+    }
+}
@@ -1,17 +1,21 @@
 use tonic::Request;
 use proto::comment_service_client::CommentServiceClient;
-use proto::{CreateCommentRequest, GetPostCommentsRequest, ToggleCommentRequest};
-use util::get_service_url;
+use proto::{
+    CommentSort, CommentStatus, CreateCommentRequest, GetDailyCommentsRequest,
+    GetPostCommentsRequest,
+    ListCommentsRequest, SetCommentsStatusRequest, ToggleCommentRequest,
+};
+use util::interceptor::connect;
 
 #[tokio::test]
 async fn test_create_comment(){
-    let client_url = get_service_url(util::Service::Comment).unwrap();
-    let mut client = CommentServiceClient::connect(client_url).await.unwrap();
+    let mut client = CommentServiceClient::new(connect(util::Service::Comment).unwrap());
     let request = Request::new(CreateCommentRequest {
         post_id: 1,
         name: "TestName1".into(),
         hashed_email: "73e19518dde2ef0fa4b28895b6a87a8b".into(),
-        content: "This is contents of a comment.".into()
+        content: "This is contents of a comment.".into(),
+        parent_id: None,
     });
 
     let response = client.create_comment(request).await.unwrap();
@@ -22,13 +26,13 @@ async fn test_create_comment(){
 #[should_panic]
 #[tokio::test]
 async fn test_create_comment_panic() {
-    let client_url = get_service_url(util::Service::Comment).unwrap();
-    let mut client = CommentServiceClient::connect(client_url).await.unwrap();
+    let mut client = CommentServiceClient::new(connect(util::Service::Comment).unwrap());
     let request = Request::new(CreateCommentRequest {
         post_id: i32::MAX,
         name: "TestName1".into(),
         hashed_email: "73e19518dde2ef0fa4b28895b6a87a8b".into(),
-        content: "This is contents of a comment.".into()
+        content: "This is contents of a comment.".into(),
+        parent_id: None,
     });
 
     let response = client.create_comment(request).await.unwrap();
@@ -37,10 +41,13 @@ async fn test_create_comment_panic() {
 
 #[tokio::test]
 async fn test_get_post_comments() {
-    let client_url = get_service_url(util::Service::Comment).unwrap();
-    let mut client = CommentServiceClient::connect(client_url).await.unwrap();
+    let mut client = CommentServiceClient::new(connect(util::Service::Comment).unwrap());
     let request = Request::new(GetPostCommentsRequest {
         post_id: 1,
+        page: None,
+        cursor: None,
+        page_size: None,
+        sort: None,
     });
     let response = client.get_post_comments(request).await.unwrap();
     dbg!(response);
@@ -48,8 +55,7 @@ async fn test_get_post_comments() {
 
 #[tokio::test]
 async fn test_toggle_comment() {
-    let client_url = get_service_url(util::Service::Comment).unwrap();
-    let mut client = CommentServiceClient::connect(client_url).await.unwrap();
+    let mut client = CommentServiceClient::new(connect(util::Service::Comment).unwrap());
     let request = Request::new(ToggleCommentRequest {
         id: 3,
     });
@@ -60,8 +66,7 @@ async fn test_toggle_comment() {
 #[should_panic]
 #[tokio::test]
 async fn test_toggle_comment_no_such_comment() {
-    let client_url = get_service_url(util::Service::Comment).unwrap();
-    let mut client = CommentServiceClient::connect(client_url).await.unwrap();
+    let mut client = CommentServiceClient::new(connect(util::Service::Comment).unwrap());
     let request = Request::new(ToggleCommentRequest {
         id: i32::MAX,
     });
@@ -72,11 +77,239 @@ async fn test_toggle_comment_no_such_comment() {
 // 应为空 post_id 的评论的 is_del 为 true，返回应为空 vector
 #[tokio::test]
 async fn test_get_post_comments_with_no_comments() {
-    let client_url = get_service_url(util::Service::Comment).unwrap();
-    let mut client = CommentServiceClient::connect(client_url).await.unwrap();
+    let mut client = CommentServiceClient::new(connect(util::Service::Comment).unwrap());
     let request = Request::new(GetPostCommentsRequest {
         post_id: 1,
+        page: None,
+        cursor: None,
+        page_size: None,
+        sort: None,
     });
     let response = client.get_post_comments(request).await.unwrap();
     assert_eq!(response.into_inner().comments, vec![]);
-}
\ No newline at end of file
+}
+
+#[tokio::test]
+async fn test_comment_replies() {
+    let mut client = CommentServiceClient::new(connect(util::Service::Comment).unwrap());
+    let new_comment = |content: &str, parent_id: Option<i32>| CreateCommentRequest {
+        post_id: 4,
+        name: "TestName1".into(),
+        hashed_email: "73e19518dde2ef0fa4b28895b6a87a8b".into(),
+        content: content.into(),
+        parent_id,
+    };
+    let root = client
+        .create_comment(Request::new(new_comment("root", None)))
+        .await
+        .unwrap()
+        .into_inner()
+        .id;
+    let reply = client
+        .create_comment(Request::new(new_comment("reply", Some(root))))
+        .await
+        .unwrap()
+        .into_inner()
+        .id;
+    let nested = client
+        .create_comment(Request::new(new_comment("nested", Some(reply))))
+        .await
+        .unwrap()
+        .into_inner()
+        .id;
+
+    // 最新的顶层评论在第一页
+    let request = Request::new(GetPostCommentsRequest {
+        post_id: 4,
+        page: None,
+        cursor: None,
+        page_size: None,
+        sort: Some(CommentSort::Newest as i32),
+    });
+    let comments = client
+        .get_post_comments(request)
+        .await
+        .unwrap()
+        .into_inner()
+        .comments;
+    let pos = |id: i32| comments.iter().position(|c| c.id == id).unwrap();
+    assert_eq!(pos(reply), pos(root) + 1);
+    assert_eq!(pos(nested), pos(reply) + 1);
+    assert_eq!(comments[pos(nested)].depth, 2);
+
+    // 父评论属于其他文章
+    let mut request = new_comment("other post", Some(root));
+    request.post_id = 5;
+    assert!(client.create_comment(Request::new(request)).await.is_err());
+}
+
+#[tokio::test]
+async fn test_post_comments_pagination() {
+    let mut client = CommentServiceClient::new(connect(util::Service::Comment).unwrap());
+    let mut ids = vec![];
+    for content in ["first", "second", "third"] {
+        let request = Request::new(CreateCommentRequest {
+            post_id: 5,
+            name: "TestName1".into(),
+            hashed_email: "73e19518dde2ef0fa4b28895b6a87a8b".into(),
+            content: content.into(),
+            parent_id: None,
+        });
+        ids.push(client.create_comment(request).await.unwrap().into_inner().id);
+    }
+    let list = |cursor: Option<String>, page_size: i32, sort: CommentSort| {
+        Request::new(GetPostCommentsRequest {
+            post_id: 5,
+            page: None,
+            cursor,
+            page_size: Some(page_size),
+            sort: Some(sort as i32),
+        })
+    };
+
+    let first = client
+        .get_post_comments(list(None, 2, CommentSort::Newest))
+        .await
+        .unwrap()
+        .into_inner();
+    let first_ids = first.comments.iter().map(|c| c.id).collect::<Vec<i32>>();
+    assert_eq!(first_ids, vec![ids[2], ids[1]]);
+    assert!(first.total >= 3);
+    assert!(first.page_total >= 2);
+    let next_cursor = first.next_cursor.unwrap();
+
+    let second = client
+        .get_post_comments(list(Some(next_cursor.clone()), 2, CommentSort::Newest))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(second.comments[0].id, ids[0]);
+
+    // 游标和排序方向不一致
+    let status = client
+        .get_post_comments(list(Some(next_cursor), 2, CommentSort::Oldest))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    let status = client
+        .get_post_comments(list(None, 101, CommentSort::Oldest))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+}
+
+#[tokio::test]
+async fn test_moderate_comments() {
+    let mut client = CommentServiceClient::new(connect(util::Service::Comment).unwrap());
+    let request = Request::new(CreateCommentRequest {
+        post_id: 4,
+        name: "TestName1".into(),
+        hashed_email: "73e19518dde2ef0fa4b28895b6a87a8b".into(),
+        content: "This comment will be moderated.".into(),
+        parent_id: None,
+    });
+    let id = client.create_comment(request).await.unwrap().into_inner().id;
+
+    // 标记为垃圾评论后不再公开显示
+    let request = Request::new(SetCommentsStatusRequest {
+        ids: vec![id, i32::MAX],
+        status: CommentStatus::Spam as i32,
+    });
+    let response = client.set_comments_status(request).await.unwrap();
+    assert_eq!(response.into_inner().ids, vec![id]);
+    // 最新的顶层评论在第一页
+    let request = Request::new(GetPostCommentsRequest {
+        post_id: 4,
+        page: None,
+        cursor: None,
+        page_size: None,
+        sort: Some(CommentSort::Newest as i32),
+    });
+    let comments = client
+        .get_post_comments(request)
+        .await
+        .unwrap()
+        .into_inner()
+        .comments;
+    assert!(comments.iter().all(|c| c.id != id));
+
+    let request = Request::new(ListCommentsRequest {
+        status: Some(CommentStatus::Spam as i32),
+        page: None,
+        is_del: None,
+    });
+    let reply = client.list_comments(request).await.unwrap().into_inner();
+    assert!(reply.comments.iter().all(|c| c.status() == CommentStatus::Spam));
+    assert!(reply.comments.iter().any(|c| c.id == id));
+
+    // 负数页码按第一页处理，过大的页码返回空列表
+    let request = Request::new(ListCommentsRequest {
+        status: Some(CommentStatus::Spam as i32),
+        page: Some(-1),
+        is_del: None,
+    });
+    let negative = client.list_comments(request).await.unwrap().into_inner();
+    assert_eq!(negative.page, 0);
+    assert_eq!(negative.comments, reply.comments);
+    let request = Request::new(ListCommentsRequest {
+        status: None,
+        page: Some(i32::MAX),
+        is_del: None,
+    });
+    let reply = client.list_comments(request).await.unwrap().into_inner();
+    assert!(reply.comments.is_empty());
+
+    let request = Request::new(SetCommentsStatusRequest {
+        ids: vec![id],
+        status: CommentStatus::Approved as i32,
+    });
+    client.set_comments_status(request).await.unwrap();
+}
+#[tokio::test]
+async fn test_spam_filter() {
+    let mut client = CommentServiceClient::new(connect(util::Service::Comment).unwrap());
+    // 超出 content 字段长度的评论直接拒绝
+    let request = Request::new(CreateCommentRequest {
+        post_id: 4,
+        name: "TestName1".into(),
+        hashed_email: "73e19518dde2ef0fa4b28895b6a87a8b".into(),
+        content: "好".repeat(201),
+        parent_id: None,
+    });
+    let status = client.create_comment(request).await.unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+    // 链接过多的评论标记为垃圾评论
+    let request = Request::new(CreateCommentRequest {
+        post_id: 4,
+        name: "TestName1".into(),
+        hashed_email: "73e19518dde2ef0fa4b28895b6a87a8b".into(),
+        content: "https://a.example https://b.example https://c.example".into(),
+        parent_id: None,
+    });
+    let reply = client.create_comment(request).await.unwrap().into_inner();
+    assert_eq!(reply.status(), CommentStatus::Spam);
+}
+
+#[tokio::test]
+async fn test_daily_comments() {
+    let mut client = CommentServiceClient::new(connect(util::Service::Comment).unwrap());
+    let request = Request::new(CreateCommentRequest {
+        post_id: 4,
+        name: "TestName1".into(),
+        hashed_email: "73e19518dde2ef0fa4b28895b6a87a8b".into(),
+        content: "Counted today.".into(),
+        parent_id: None,
+    });
+    client.create_comment(request).await.unwrap();
+
+    let request = Request::new(GetDailyCommentsRequest {
+        post_id: Some(4),
+        start: "".into(),
+        end: "".into(),
+    });
+    let days = client.get_daily_comments(request).await.unwrap().into_inner().days;
+    // 默认最近 30 天，最后一天为今天
+    assert_eq!(days.len(), 30);
+    assert!(days.last().unwrap().comments > 0);
+}
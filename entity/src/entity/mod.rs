@@ -5,4 +5,8 @@ pub mod prelude;
 pub mod admin;
 pub mod comment;
 pub mod post;
+pub mod post_revision;
+pub mod post_tag;
+pub mod post_view_daily;
+pub mod sea_orm_active_enums;
 pub mod tag;
@@ -1,5 +1,6 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.0.0
 
+use super::sea_orm_active_enums::PostStatus;
 use sea_orm::entity::prelude::*;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
@@ -9,21 +10,28 @@ pub struct Model {
     pub id: i32,
     pub title: String,
     pub summary: String,
-    pub md_path: String,
-    pub html_path: String,
+    #[sea_orm(column_type = "Text")]
+    pub md_content: String,
+    #[sea_orm(column_type = "Text")]
+    pub html_content: String,
     pub hit: i32,
     pub words_len: Option<i32>,
     pub is_del: bool,
     pub publish_time: DateTimeWithTimeZone,
     pub update_time: Option<DateTimeWithTimeZone>,
-    #[sea_orm(column_type = "VarBinary(StringLen::None)", nullable)]
-    pub tag_id: Option<Vec<u8>>,
+    pub status: PostStatus,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(has_many = "super::comment::Entity")]
     Comment,
+    #[sea_orm(has_many = "super::post_revision::Entity")]
+    PostRevision,
+    #[sea_orm(has_many = "super::post_tag::Entity")]
+    PostTag,
+    #[sea_orm(has_many = "super::post_view_daily::Entity")]
+    PostViewDaily,
 }
 
 impl Related<super::comment::Entity> for Entity {
@@ -32,4 +40,31 @@ impl Related<super::comment::Entity> for Entity {
     }
 }
 
+impl Related<super::post_revision::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PostRevision.def()
+    }
+}
+
+impl Related<super::post_tag::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PostTag.def()
+    }
+}
+
+impl Related<super::post_view_daily::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PostViewDaily.def()
+    }
+}
+
+impl Related<super::tag::Entity> for Entity {
+    fn to() -> RelationDef {
+        super::post_tag::Relation::Tag.def()
+    }
+    fn via() -> Option<RelationDef> {
+        Some(super::post_tag::Relation::Post.def().rev())
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -3,4 +3,7 @@
 pub use super::admin::Entity as Admin;
 pub use super::comment::Entity as Comment;
 pub use super::post::Entity as Post;
+pub use super::post_revision::Entity as PostRevision;
+pub use super::post_tag::Entity as PostTag;
+pub use super::post_view_daily::Entity as PostViewDaily;
 pub use super::tag::Entity as Tag;
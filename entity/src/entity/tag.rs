@@ -12,6 +12,24 @@ pub struct Model {
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {}
+pub enum Relation {
+    #[sea_orm(has_many = "super::post_tag::Entity")]
+    PostTag,
+}
+
+impl Related<super::post_tag::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PostTag.def()
+    }
+}
+
+impl Related<super::post::Entity> for Entity {
+    fn to() -> RelationDef {
+        super::post_tag::Relation::Post.def()
+    }
+    fn via() -> Option<RelationDef> {
+        Some(super::post_tag::Relation::Tag.def().rev())
+    }
+}
 
 impl ActiveModelBehavior for ActiveModel {}
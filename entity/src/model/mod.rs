@@ -7,30 +7,47 @@ pub struct Post {
     pub id: i32,
     pub title: String,
     pub summary: String,
-    // pub md_path: String,
-    // pub html_path: String,
     pub hit: i32,
     pub words_len: i32,
     // pub is_del: bool,
     pub publish_time: i64,
     pub update_time: i64,
     pub tag_id: Vec<i32>,
+    pub status: String, // published, draft, scheduled, archived
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub highlight: Option<SearchHighlight>, // 仅按关键字搜索时返回
 }
 
 impl From<proto::Post> for Post {
     fn from(p: proto::Post) -> Self {
+        let status = p.status().as_str_name().to_lowercase();
         Self {
             id: p.id,
             title: p.title,
             summary: p.summary,
-            // md_path: p.md_path,
-            // html_path: p.html_path,
             hit: p.hit,
             words_len: p.words_len,
             // is_del: p.is_del,
             publish_time: p.publish_time.unwrap_or_default().seconds,
             update_time: p.update_time.unwrap_or_default().seconds,
+            status,
             tag_id: p.tag_id,
+            highlight: p.highlight.map(Into::into),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct SearchHighlight {
+    pub title: String,
+    pub snippet: String,
+}
+
+impl From<proto::SearchHighlight> for SearchHighlight {
+    fn from(h: proto::SearchHighlight) -> Self {
+        Self {
+            title: h.title,
+            snippet: h.snippet,
         }
     }
 }
@@ -40,12 +57,72 @@ pub struct ListPostRes {
     pub page: i32,
     pub page_total: i32,
     pub posts: Vec<Post>,
+    pub next_cursor: Option<String>, // 下一页的游标，没有下一页时为 null
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<i64>,
 }
 
 #[derive(Serialize)]
 pub struct GetPostRes {
     pub post: Post,
     pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub md_content: Option<String>, // 仅后台编辑时返回
+}
+
+#[derive(Serialize)]
+pub struct PostRevision {
+    pub id: i32,
+    pub post_id: i32,
+    pub title: String,
+    pub tag_id: Vec<i32>,
+    pub summary: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub md_content: Option<String>,
+    pub editor_id: Option<i32>,
+    pub created_at: i64,
+}
+
+impl From<proto::PostRevision> for PostRevision {
+    fn from(r: proto::PostRevision) -> Self {
+        Self {
+            id: r.id,
+            post_id: r.post_id,
+            title: r.title,
+            tag_id: r.tag_id,
+            summary: r.summary,
+            md_content: r.md_content,
+            editor_id: r.editor_id,
+            created_at: r.created_at.unwrap_or_default().seconds,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct DiffLine {
+    pub op: &'static str, // equal / insert / delete
+    pub content: String,
+}
+
+impl From<proto::DiffLine> for DiffLine {
+    fn from(l: proto::DiffLine) -> Self {
+        let op = match l.op() {
+            proto::diff_line::Op::Equal => "equal",
+            proto::diff_line::Op::Insert => "insert",
+            proto::diff_line::Op::Delete => "delete",
+        };
+        Self {
+            op,
+            content: l.content,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct DiffPostRevisionsRes {
+    pub from: Option<PostRevision>,
+    pub to: Option<PostRevision>,
+    pub lines: Vec<DiffLine>,
 }
 
 #[derive(Serialize)]
@@ -71,10 +148,15 @@ pub struct Comment {
     hashed_email: String,
     content: String,
     created_at: i64,
+    parent_id: Option<i32>,
+    depth: i32,
+    status: String, // approved, pending, spam, rejected
+    is_del: bool,
 }
 
 impl From<proto::Comment> for Comment {
     fn from(c: proto::Comment) -> Self {
+        let status = c.status().as_str_name().to_lowercase();
         Self {
             id: c.id,
             post_id: c.post_id,
@@ -82,6 +164,102 @@ impl From<proto::Comment> for Comment {
             hashed_email: c.hashed_email,
             content: c.content,
             created_at: c.created_at.unwrap_or_default().seconds,
+            parent_id: c.parent_id,
+            depth: c.depth,
+            status,
+            is_del: c.is_del,
         }
     }
-}
\ No newline at end of file
+}
+
+#[derive(Serialize)]
+pub struct ListCommentsRes {
+    pub page: i32,
+    pub page_total: i32,
+    pub comments: Vec<Comment>,
+}
+
+#[derive(Serialize)]
+pub struct PostCommentsRes {
+    pub page: i32,
+    pub page_total: i32, // 按顶层评论计算
+    pub total: i64, // 可见评论总数，包括回复
+    pub comments: Vec<Comment>,
+    pub next_cursor: Option<String>, // 下一页的游标，没有下一页时为 null
+}
+
+#[derive(Serialize)]
+pub struct Admin {
+    pub id: i32,
+    pub email: String,
+    pub is_del: bool,
+}
+
+impl From<proto::Admin> for Admin {
+    fn from(a: proto::Admin) -> Self {
+        Self {
+            id: a.id,
+            email: a.email,
+            is_del: a.is_del,
+        }
+    }
+}
+
+/// 统计接口中每天的数量
+#[derive(Serialize)]
+pub struct DailyCount {
+    pub day: String, // YYYY-MM-DD
+    pub count: i64,
+}
+
+impl From<proto::DailyViews> for DailyCount {
+    fn from(d: proto::DailyViews) -> Self {
+        Self {
+            day: d.day,
+            count: d.views,
+        }
+    }
+}
+
+impl From<proto::DailyComments> for DailyCount {
+    fn from(d: proto::DailyComments) -> Self {
+        Self {
+            day: d.day,
+            count: d.comments,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct TopPost {
+    pub id: i32,
+    pub title: String,
+    pub views: i64,
+}
+
+impl From<proto::TopPost> for TopPost {
+    fn from(p: proto::TopPost) -> Self {
+        Self {
+            id: p.id,
+            title: p.title,
+            views: p.views,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct TagPostCount {
+    pub tag_id: i32,
+    pub name: String,
+    pub posts: i64,
+}
+
+impl From<proto::TagPostCount> for TagPostCount {
+    fn from(t: proto::TagPostCount) -> Self {
+        Self {
+            tag_id: t.tag_id,
+            name: t.name,
+            posts: t.posts,
+        }
+    }
+}
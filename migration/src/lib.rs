@@ -0,0 +1,30 @@
+pub use sea_orm_migration::prelude::*;
+
+mod m20240816_000001_create_table;
+mod m20261018_000001_create_post_tag;
+mod m20261018_000002_post_content;
+mod m20261018_000003_create_post_revision;
+mod m20261018_000004_post_status;
+mod m20261018_000005_post_search;
+mod m20261018_000006_comment_parent;
+mod m20261018_000007_comment_status;
+mod m20261018_000008_create_post_view_daily;
+
+pub struct Migrator;
+
+#[async_trait::async_trait]
+impl MigratorTrait for Migrator {
+    fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+        vec![
+            Box::new(m20240816_000001_create_table::Migration),
+            Box::new(m20261018_000001_create_post_tag::Migration),
+            Box::new(m20261018_000002_post_content::Migration),
+            Box::new(m20261018_000003_create_post_revision::Migration),
+            Box::new(m20261018_000004_post_status::Migration),
+            Box::new(m20261018_000005_post_search::Migration),
+            Box::new(m20261018_000006_comment_parent::Migration),
+            Box::new(m20261018_000007_comment_status::Migration),
+            Box::new(m20261018_000008_create_post_view_daily::Migration),
+        ]
+    }
+}
@@ -0,0 +1,178 @@
+// 初始表结构，与最初 pg_dump 导出的 schema 一致
+// 已经用该 dump 建好的数据库执行时会跳过已存在的表与索引
+
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Admin::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Admin::Id))
+                    .col(string_len(Admin::Email, 255))
+                    .col(string_len(Admin::Password, 255))
+                    .col(boolean(Admin::IsDel).default(false))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Tag::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Tag::Id))
+                    .col(string_len(Tag::Name, 100))
+                    .col(boolean(Tag::IsDel).default(false))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Post::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Post::Id))
+                    .col(string_len(Post::Title, 255))
+                    .col(string_len(Post::Summary, 255))
+                    .col(string_len(Post::MdPath, 255))
+                    .col(string_len(Post::HtmlPath, 255))
+                    .col(integer(Post::Hit).default(0))
+                    .col(integer_null(Post::WordsLen).default(0))
+                    .col(boolean(Post::IsDel).default(false))
+                    .col(
+                        timestamp_with_time_zone(Post::PublishTime)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(timestamp_with_time_zone_null(Post::UpdateTime))
+                    .col(binary_null(Post::TagId))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Comment::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Comment::Id))
+                    .col(integer(Comment::PostId))
+                    .col(string_len(Comment::Name, 255))
+                    .col(string_len_null(Comment::HashedEmail, 255))
+                    .col(string_len_null(Comment::Content, 200))
+                    .col(
+                        timestamp_with_time_zone(Comment::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(boolean(Comment::IsDel).default(false))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("comment_post_id_fk")
+                            .from(Comment::Table, Comment::PostId)
+                            .to(Post::Table, Post::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("admin_email_idx")
+                    .table(Admin::Table)
+                    .col(Admin::Email)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("post_publish_time_idx")
+                    .table(Post::Table)
+                    .col(Post::PublishTime)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("comment_post_id_idx")
+                    .table(Comment::Table)
+                    .col(Comment::PostId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Comment::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Post::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Tag::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Admin::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Admin {
+    Table,
+    Id,
+    Email,
+    Password,
+    IsDel,
+}
+
+#[derive(DeriveIden)]
+enum Tag {
+    Table,
+    Id,
+    Name,
+    IsDel,
+}
+
+#[derive(DeriveIden)]
+enum Post {
+    Table,
+    Id,
+    Title,
+    Summary,
+    MdPath,
+    HtmlPath,
+    Hit,
+    WordsLen,
+    IsDel,
+    PublishTime,
+    UpdateTime,
+    TagId,
+}
+
+#[derive(DeriveIden)]
+enum Comment {
+    Table,
+    Id,
+    PostId,
+    Name,
+    HashedEmail,
+    Content,
+    CreatedAt,
+    IsDel,
+}
@@ -0,0 +1,127 @@
+// 文章与标签的多对多关联表，并迁移 post.tag_id 中 X1XX2X 形式的旧数据
+
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PostTag::Table)
+                    .if_not_exists()
+                    .col(integer(PostTag::PostId))
+                    .col(integer(PostTag::TagId))
+                    .primary_key(
+                        Index::create()
+                            .name("post_tag_pkey")
+                            .col(PostTag::PostId)
+                            .col(PostTag::TagId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("post_tag_post_id_fk")
+                            .from(PostTag::Table, PostTag::PostId)
+                            .to(Post::Table, Post::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("post_tag_tag_id_fk")
+                            .from(PostTag::Table, PostTag::TagId)
+                            .to(Tag::Table, Tag::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 按标签查文章时使用
+        manager
+            .create_index(
+                Index::create()
+                    .name("post_tag_tag_id_post_id_idx")
+                    .table(PostTag::Table)
+                    .col(PostTag::TagId)
+                    .col(PostTag::PostId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        // 初始迁移和 dump 建立的 post 表都有 tag_id，这里检查是为了兼容没有该列的旧版初始迁移
+        if manager.has_column("post", "tag_id").await? {
+            let db = manager.get_connection();
+            db.execute_unprepared(
+                r#"INSERT INTO post_tag (post_id, tag_id)
+                SELECT DISTINCT p.id, t.id
+                FROM post p
+                CROSS JOIN LATERAL regexp_split_to_table(convert_from(p.tag_id, 'UTF8'), 'X') AS part
+                JOIN tag t ON part ~ '^[0-9]+$' AND t.id = part::integer
+                WHERE p.tag_id IS NOT NULL
+                ON CONFLICT DO NOTHING"#,
+            )
+            .await?;
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Post::Table)
+                        .drop_column(Post::TagId)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 还原 post.tag_id 及其 X1XX2X 形式的编码
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Post::Table)
+                    .add_column_if_not_exists(binary_null(Post::TagId))
+                    .to_owned(),
+            )
+            .await?;
+        let db = manager.get_connection();
+        db.execute_unprepared(
+            r#"UPDATE post p
+            SET tag_id = convert_to(s.tags, 'UTF8')
+            FROM (
+                SELECT post_id, string_agg('X' || tag_id || 'X', '' ORDER BY tag_id) AS tags
+                FROM post_tag
+                GROUP BY post_id
+            ) s
+            WHERE p.id = s.post_id"#,
+        )
+        .await?;
+        manager
+            .drop_table(Table::drop().table(PostTag::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PostTag {
+    Table,
+    PostId,
+    TagId,
+}
+
+#[derive(DeriveIden)]
+enum Post {
+    Table,
+    Id,
+    TagId,
+}
+
+#[derive(DeriveIden)]
+enum Tag {
+    Table,
+    Id,
+}
@@ -0,0 +1,91 @@
+// 文章内容保存到数据库中，不再依赖 post-srv 本地的 md_path / html_path 文件
+
+use sea_orm_migration::sea_orm::{ConnectionTrait, DbBackend, Statement};
+use sea_orm_migration::{prelude::*, schema::*};
+use std::fs;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Post::Table)
+                    .add_column_if_not_exists(text(Post::MdContent).default(""))
+                    .add_column_if_not_exists(text(Post::HtmlContent).default(""))
+                    .to_owned(),
+            )
+            .await?;
+
+        if !manager.has_column("post", "md_path").await? {
+            return Ok(());
+        }
+
+        // 尽量从原来的文件中读取内容，读取不到的文章内容留空
+        let db = manager.get_connection();
+        let rows = db
+            .query_all(Statement::from_string(
+                DbBackend::Postgres,
+                "SELECT id, md_path, html_path FROM post",
+            ))
+            .await?;
+        for row in rows {
+            let id: i32 = row.try_get("", "id")?;
+            let md_path: String = row.try_get("", "md_path")?;
+            let html_path: String = row.try_get("", "html_path")?;
+            let (md, html) = match fs::read_to_string(&md_path) {
+                Ok(md) => {
+                    let html = markdown::to_html(&md);
+                    (md, html)
+                }
+                Err(_) => (
+                    String::new(),
+                    fs::read_to_string(&html_path).unwrap_or_default(),
+                ),
+            };
+            db.execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                "UPDATE post SET md_content = $1, html_content = $2 WHERE id = $3",
+                [md.into(), html.into(), id.into()],
+            ))
+            .await?;
+        }
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Post::Table)
+                    .drop_column(Post::MdPath)
+                    .drop_column(Post::HtmlPath)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 文章内容不会再写回文件
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Post::Table)
+                    .add_column(string_len(Post::MdPath, 255).default(""))
+                    .add_column(string_len(Post::HtmlPath, 255).default(""))
+                    .drop_column(Post::MdContent)
+                    .drop_column(Post::HtmlContent)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Post {
+    Table,
+    MdPath,
+    HtmlPath,
+    MdContent,
+    HtmlContent,
+}
@@ -0,0 +1,102 @@
+// 文章修订记录，每次创建/修改文章时保存一份快照
+
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PostRevision::Table)
+                    .if_not_exists()
+                    .col(pk_auto(PostRevision::Id))
+                    .col(integer(PostRevision::PostId))
+                    .col(string_len(PostRevision::Title, 255))
+                    .col(string_len(PostRevision::Summary, 255))
+                    .col(text(PostRevision::MdContent))
+                    .col(array(PostRevision::TagId, ColumnType::Integer))
+                    .col(integer_null(PostRevision::EditorId))
+                    .col(
+                        timestamp_with_time_zone(PostRevision::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("post_revision_post_id_fk")
+                            .from(PostRevision::Table, PostRevision::PostId)
+                            .to(Post::Table, Post::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("post_revision_editor_id_fk")
+                            .from(PostRevision::Table, PostRevision::EditorId)
+                            .to(Admin::Table, Admin::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("post_revision_post_id_idx")
+                    .table(PostRevision::Table)
+                    .col(PostRevision::PostId)
+                    .col(PostRevision::Id)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        // 已有的文章以当前内容作为第一个修订
+        let db = manager.get_connection();
+        db.execute_unprepared(
+            r#"INSERT INTO post_revision (post_id, title, summary, md_content, tag_id, created_at)
+            SELECT p.id, p.title, p.summary, p.md_content,
+                COALESCE(ARRAY(SELECT pt.tag_id FROM post_tag pt WHERE pt.post_id = p.id ORDER BY pt.tag_id), '{}'),
+                COALESCE(p.update_time, p.publish_time)
+            FROM post p
+            WHERE NOT EXISTS (SELECT 1 FROM post_revision r WHERE r.post_id = p.id)"#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PostRevision::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PostRevision {
+    Table,
+    Id,
+    PostId,
+    Title,
+    Summary,
+    MdContent,
+    TagId,
+    EditorId,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Post {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Admin {
+    Table,
+    Id,
+}
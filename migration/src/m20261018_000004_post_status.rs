@@ -0,0 +1,91 @@
+// 文章状态：草稿 / 定时发布 / 已发布 / 已归档
+
+use sea_orm_migration::prelude::extension::postgres::Type;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(PostStatus::Enum)
+                    .values([
+                        PostStatus::Draft,
+                        PostStatus::Scheduled,
+                        PostStatus::Published,
+                        PostStatus::Archived,
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        // 已有的文章都视为已发布
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Post::Table)
+                    .add_column(
+                        enumeration(
+                            Post::Status,
+                            PostStatus::Enum,
+                            [
+                                PostStatus::Draft,
+                                PostStatus::Scheduled,
+                                PostStatus::Published,
+                                PostStatus::Archived,
+                            ],
+                        )
+                        .default(Expr::cust("'published'::post_status")),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("post_status_publish_time_idx")
+                    .table(Post::Table)
+                    .col(Post::Status)
+                    .col(Post::PublishTime)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Post::Table)
+                    .drop_column(Post::Status)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_type(Type::drop().name(PostStatus::Enum).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Post {
+    Table,
+    Status,
+    PublishTime,
+}
+
+#[derive(DeriveIden)]
+enum PostStatus {
+    #[sea_orm(iden = "post_status")]
+    Enum,
+    Draft,
+    Scheduled,
+    Published,
+    Archived,
+}
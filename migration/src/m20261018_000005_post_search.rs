@@ -0,0 +1,64 @@
+// 文章全文搜索：标题、摘要和 markdown 原文生成 tsvector
+//
+// simple 分词器不会切分中文，search_split 先把每个汉字和全角标点用空格隔开，
+// 查询时用 phraseto_tsquery 要求这些字相邻，相当于按短语匹配
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        db.execute_unprepared(
+            r"CREATE OR REPLACE FUNCTION search_split(text) RETURNS text
+            LANGUAGE sql IMMUTABLE PARALLEL SAFE
+            AS $$ SELECT regexp_replace($1, '([\u3000-\u303f\u3400-\u9fff\uf900-\ufaff\uff00-\uffef])', ' \1 ', 'g') $$",
+        )
+        .await?;
+        db.execute_unprepared(
+            "ALTER TABLE post ADD COLUMN search_vector tsvector GENERATED ALWAYS AS (
+                setweight(to_tsvector('simple', search_split(title)), 'A')
+                || setweight(to_tsvector('simple', search_split(coalesce(summary, ''))), 'B')
+                || setweight(to_tsvector('simple', search_split(md_content)), 'C')
+            ) STORED",
+        )
+        .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("post_search_vector_idx")
+                    .table(Post::Table)
+                    .col(Post::SearchVector)
+                    .index_type(IndexType::Custom(SeaRc::new(Alias::new("GIN"))))
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Post::Table)
+                    .drop_column(Post::SearchVector)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .get_connection()
+            .execute_unprepared("DROP FUNCTION IF EXISTS search_split(text)")
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Post {
+    Table,
+    SearchVector,
+}
@@ -0,0 +1,60 @@
+// 评论回复：parent_id 指向同一篇文章下的另一条评论
+
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Comment::Table)
+                    .add_column(integer_null(Comment::ParentId))
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name("comment_parent_id_fk")
+                            .from_tbl(Comment::Table)
+                            .from_col(Comment::ParentId)
+                            .to_tbl(Comment::Table)
+                            .to_col(Comment::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("comment_post_id_parent_id_idx")
+                    .table(Comment::Table)
+                    .col(Comment::PostId)
+                    .col(Comment::ParentId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Comment::Table)
+                    .drop_column(Comment::ParentId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Comment {
+    Table,
+    Id,
+    PostId,
+    ParentId,
+}
@@ -0,0 +1,92 @@
+// 评论审核状态：待审核 / 已通过 / 垃圾评论 / 已拒绝
+
+use sea_orm_migration::prelude::extension::postgres::Type;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(CommentStatus::Enum)
+                    .values([
+                        CommentStatus::Pending,
+                        CommentStatus::Approved,
+                        CommentStatus::Spam,
+                        CommentStatus::Rejected,
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        // 已有的评论都视为已通过
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Comment::Table)
+                    .add_column(
+                        enumeration(
+                            Comment::Status,
+                            CommentStatus::Enum,
+                            [
+                                CommentStatus::Pending,
+                                CommentStatus::Approved,
+                                CommentStatus::Spam,
+                                CommentStatus::Rejected,
+                            ],
+                        )
+                        .default(Expr::cust("'approved'::comment_status")),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 审核列表按状态查询所有文章的评论
+        manager
+            .create_index(
+                Index::create()
+                    .name("comment_status_created_at_idx")
+                    .table(Comment::Table)
+                    .col(Comment::Status)
+                    .col(Comment::CreatedAt)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Comment::Table)
+                    .drop_column(Comment::Status)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_type(Type::drop().name(CommentStatus::Enum).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Comment {
+    Table,
+    Status,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum CommentStatus {
+    #[sea_orm(iden = "comment_status")]
+    Enum,
+    Pending,
+    Approved,
+    Spam,
+    Rejected,
+}
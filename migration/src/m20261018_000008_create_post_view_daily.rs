@@ -0,0 +1,68 @@
+// 文章每天的浏览量，由 post-srv 批量累加
+
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PostViewDaily::Table)
+                    .if_not_exists()
+                    .col(integer(PostViewDaily::PostId))
+                    .col(date(PostViewDaily::Day))
+                    .col(integer(PostViewDaily::Views).default(0))
+                    .primary_key(
+                        Index::create()
+                            .name("post_view_daily_pkey")
+                            .col(PostViewDaily::PostId)
+                            .col(PostViewDaily::Day),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("post_view_daily_post_id_fk")
+                            .from(PostViewDaily::Table, PostViewDaily::PostId)
+                            .to(Post::Table, Post::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 按日期统计全站浏览量时使用
+        manager
+            .create_index(
+                Index::create()
+                    .name("post_view_daily_day_idx")
+                    .table(PostViewDaily::Table)
+                    .col(PostViewDaily::Day)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PostViewDaily::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PostViewDaily {
+    Table,
+    PostId,
+    Day,
+    Views,
+}
+
+#[derive(DeriveIden)]
+enum Post {
+    Table,
+    Id,
+}
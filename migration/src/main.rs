@@ -0,0 +1,8 @@
+use sea_orm_migration::prelude::*;
+
+// 用法：cargo run -p migration -- [up|down|status|fresh|refresh|reset]
+// 数据库地址读取自环境变量 DATABASE_URL
+#[tokio::main]
+async fn main() {
+    cli::run_cli(migration::Migrator).await;
+}
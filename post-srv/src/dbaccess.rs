@@ -1,122 +1,293 @@
+use std::collections::HashMap;
+
 use anyhow::{anyhow, Result};
-use entity::entity::post;
 use entity::entity::post::Column;
+use entity::entity::sea_orm_active_enums::PostStatus;
+use entity::entity::{post, post_revision, post_tag, post_view_daily, tag};
 use sea_orm::prelude::{DateTimeWithTimeZone, Expr};
+use sea_orm::sea_query::{Func, OnConflict, Query, SelectStatement, SimpleExpr};
+use sea_orm::sqlx::types::chrono::{Local, NaiveDate};
 use sea_orm::ActiveValue::Set;
 use sea_orm::{
-    ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
-    QuerySelect,
+    ActiveEnum, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, EntityTrait,
+    JoinType, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, RelationTrait, Select,
+    TransactionTrait,
 };
-use sea_orm::sqlx::types::chrono::Local;
 use util::gen_html;
 
+use crate::search::{search_query, search_terms};
+use tracing::instrument;
+
+#[allow(clippy::too_many_arguments)]
+#[instrument(skip_all)]
 pub async fn insert_new_post(
     db: &DatabaseConnection,
     title: String,
     tag_id: Vec<i32>,
-    md_path: String,
+    content: String,
     summary: String,
+    editor_id: Option<i32>,
+    status: PostStatus,
+    publish_time: Option<DateTimeWithTimeZone>,
 ) -> Result<i32> {
-    let (html_path, words_len) = gen_html(&md_path)?;
-    let new_post = post::ActiveModel {
-        title: Set(title),
-        tag_id: Set(Some(util::tags_to_u8(tag_id))),
-        md_path: Set(md_path),
-        html_path: Set(html_path),
+    let tag_id = normalize_tags(tag_id);
+    let (html_content, words_len) = gen_html(&content);
+    let mut new_post = post::ActiveModel {
+        title: Set(title.clone()),
+        md_content: Set(content.clone()),
+        html_content: Set(html_content),
         words_len: Set(Some(words_len)),
+        summary: Set(summary.clone()),
+        status: Set(status),
+        ..Default::default()
+    };
+    if let Some(publish_time) = publish_time {
+        new_post.publish_time = Set(publish_time);
+    }
+    let txn = db.begin().await?;
+    let res = post::Entity::insert(new_post).exec(&txn).await?;
+    let id = res.last_insert_id;
+    insert_post_tags(&txn, id, &tag_id).await?;
+    let revision = post_revision::ActiveModel {
+        post_id: Set(id),
+        title: Set(title),
         summary: Set(summary),
+        md_content: Set(content),
+        tag_id: Set(tag_id),
+        editor_id: Set(editor_id),
         ..Default::default()
     };
-    let res = post::Entity::insert(new_post).exec(db).await?;
-    Ok(res.last_insert_id)
+    post_revision::Entity::insert(revision).exec(&txn).await?;
+    txn.commit().await?;
+    Ok(id)
 }
 
+/// update_post 修改文章并记录一个新的修订，返回修订 id，文章不存在时返回 None
+#[allow(clippy::too_many_arguments)]
+#[instrument(skip_all)]
 pub async fn update_post(
     db: &DatabaseConnection,
     id: i32,
     title: String,
     tag_id: Vec<i32>,
-    md_path: String,
+    content: String,
     summary: String,
-) -> Result<u64> {
-    let res = post::Entity::update_many()
-        .filter(Column::Id.eq(id))
-        .col_expr(Column::Title, Expr::value(title))
-        .col_expr(Column::TagId, Expr::value(Some(util::tags_to_u8(tag_id))))
-        .col_expr(Column::MdPath, Expr::value(md_path))
-        .col_expr(Column::Summary, Expr::value(Some(summary)))
-        .col_expr(Column::UpdateTime, Expr::value(Some(DateTimeWithTimeZone::from(Local::now()))))
-        .exec(db)
+    editor_id: Option<i32>,
+    status: Option<PostStatus>,
+    publish_time: Option<DateTimeWithTimeZone>,
+) -> Result<Option<i32>> {
+    let tag_id = normalize_tags(tag_id);
+    let (html_content, words_len) = gen_html(&content);
+    let txn = db.begin().await?;
+    let mut update = post::Entity::update_many().filter(Column::Id.eq(id));
+    if let Some(status) = status {
+        update = update.col_expr(
+            Column::Status,
+            Expr::val(status).as_enum(PostStatus::name()),
+        );
+    }
+    if let Some(publish_time) = publish_time {
+        update = update.col_expr(Column::PublishTime, Expr::value(publish_time));
+    }
+    let res = update
+        .col_expr(Column::Title, Expr::value(title.clone()))
+        .col_expr(Column::MdContent, Expr::value(content.clone()))
+        .col_expr(Column::HtmlContent, Expr::value(html_content))
+        .col_expr(Column::WordsLen, Expr::value(Some(words_len)))
+        .col_expr(Column::Summary, Expr::value(Some(summary.clone())))
+        .col_expr(
+            Column::UpdateTime,
+            Expr::value(Some(DateTimeWithTimeZone::from(Local::now()))),
+        )
+        .exec(&txn)
+        .await?;
+    if res.rows_affected == 0 {
+        return Ok(None);
+    }
+    post_tag::Entity::delete_many()
+        .filter(post_tag::Column::PostId.eq(id))
+        .exec(&txn)
         .await?;
-    Ok(res.rows_affected)
+    insert_post_tags(&txn, id, &tag_id).await?;
+    let revision = post_revision::ActiveModel {
+        post_id: Set(id),
+        title: Set(title),
+        summary: Set(summary),
+        md_content: Set(content),
+        tag_id: Set(tag_id),
+        editor_id: Set(editor_id),
+        ..Default::default()
+    };
+    let revision = post_revision::Entity::insert(revision).exec(&txn).await?;
+    txn.commit().await?;
+    Ok(Some(revision.last_insert_id))
+}
+
+fn normalize_tags(mut tag_id: Vec<i32>) -> Vec<i32> {
+    tag_id.sort_unstable();
+    tag_id.dedup();
+    tag_id
+}
+
+#[instrument(skip_all)]
+async fn insert_post_tags<C: ConnectionTrait>(db: &C, post_id: i32, tag_id: &[i32]) -> Result<()> {
+    if tag_id.is_empty() {
+        return Ok(());
+    }
+    let post_tags = tag_id.iter().map(|tag_id| post_tag::ActiveModel {
+        post_id: Set(post_id),
+        tag_id: Set(*tag_id),
+    });
+    post_tag::Entity::insert_many(post_tags).exec(db).await?;
+    Ok(())
 }
 
-pub async fn select_record_total(
+/// select_post_tags 查询文章对应的标签，返回 post_id -> tag_id 列表
+#[instrument(skip_all)]
+pub async fn select_post_tags(
     db: &DatabaseConnection,
-    tag_id: Option<i32>,
-    keyword: Option<String>,
-    is_del: Option<bool>,
-    start: Option<DateTimeWithTimeZone>,
-    end: Option<DateTimeWithTimeZone>,
-) -> Result<u64> {
-    let mut select = post::Entity::find();
-    if let Some(tag_id) = tag_id {
-        // 这就是一坨 💩
-        select = select.filter(Expr::cust(format!(
-            "convert_from(tag_id, 'UTF8') LIKE '%X{}X%'",
-            tag_id
-        )));
-    }
-    if let Some(keyword) = keyword {
-        select = select.filter(Column::Title.contains(keyword));
+    post_id: &[i32],
+) -> Result<HashMap<i32, Vec<i32>>> {
+    let res = post_tag::Entity::find()
+        .filter(post_tag::Column::PostId.is_in(post_id.to_owned()))
+        .order_by_asc(post_tag::Column::TagId)
+        .all(db)
+        .await?;
+    let mut tags: HashMap<i32, Vec<i32>> = HashMap::new();
+    for row in res {
+        tags.entry(row.post_id).or_default().push(row.tag_id);
     }
-    if let Some(is_del) = is_del {
-        select = select.filter(Column::IsDel.eq(is_del));
+    Ok(tags)
+}
+
+/// 包含指定标签的文章 id，match_all 为 true 时需包含全部标签
+fn post_id_with_tags(tag_id: Vec<i32>, match_all: bool) -> SelectStatement {
+    let tag_id = normalize_tags(tag_id);
+    let tag_count = tag_id.len() as i32;
+    let mut query = Query::select();
+    query
+        .column(post_tag::Column::PostId)
+        .from(post_tag::Entity)
+        .and_where(post_tag::Column::TagId.is_in(tag_id));
+    if match_all {
+        query
+            .group_by_col(post_tag::Column::PostId)
+            .and_having(Expr::expr(Func::count(Expr::col(post_tag::Column::TagId))).eq(tag_count));
     }
-    if start.is_some() && end.is_some() {
-        select = select.filter(Column::PublishTime.between(start, end));
+    query.to_owned()
+}
+
+/// 文章列表的查询条件
+pub struct PostFilter {
+    pub tag_id: Vec<i32>,
+    pub match_all_tags: bool,
+    pub keyword: Option<String>,
+    pub is_del: Option<bool>,
+    pub start: Option<DateTimeWithTimeZone>,
+    pub end: Option<DateTimeWithTimeZone>,
+    pub status: Option<PostStatus>,
+}
+
+impl PostFilter {
+    fn apply(self, mut select: Select<post::Entity>) -> Select<post::Entity> {
+        if !self.tag_id.is_empty() {
+            select = select.filter(
+                Column::Id.in_subquery(post_id_with_tags(self.tag_id, self.match_all_tags)),
+            );
+        }
+        if let Some(keyword) = self.keyword {
+            // 全文搜索之外保留标题的子串匹配，输入不完整的单词时也能找到
+            let mut condition = Condition::any().add(Column::Title.contains(&keyword));
+            if let Some(query) = search_query(&search_terms(&keyword)) {
+                condition = condition.add(Expr::cust_with_exprs("search_vector @@ ($1)", [query]));
+            }
+            select = select.filter(condition);
+        }
+        if let Some(is_del) = self.is_del {
+            select = select.filter(Column::IsDel.eq(is_del));
+        }
+        if self.start.is_some() && self.end.is_some() {
+            select = select.filter(Column::PublishTime.between(self.start, self.end));
+        }
+        if let Some(status) = self.status {
+            select = select.filter(status_condition(status));
+        }
+        select
     }
+}
 
+/// status_condition 定时发布的文章到达 publish_time 后视为已发布
+fn status_condition(status: PostStatus) -> Condition {
+    let published = Expr::col((post::Entity, Column::PublishTime)).lte(Expr::current_timestamp());
+    match status {
+        PostStatus::Published => Condition::all()
+            .add(Column::Status.is_in([PostStatus::Published, PostStatus::Scheduled]))
+            .add(published),
+        PostStatus::Scheduled => Condition::all()
+            .add(Column::Status.is_in([PostStatus::Published, PostStatus::Scheduled]))
+            .add(published.not()),
+        status => Condition::all().add(Column::Status.eq(status)),
+    }
+}
+
+#[instrument(skip_all)]
+pub async fn select_record_total(db: &DatabaseConnection, filter: PostFilter) -> Result<u64> {
+    let select = filter.apply(post::Entity::find());
     Ok(select.count(db).await?)
 }
 
-#[allow(clippy::too_many_arguments)]
+/// 文章列表的排序方式
+pub enum PostSort {
+    Newest,
+    Relevance,
+    MostHits,
+}
+
+/// select_posts after 为上一页最后一篇文章的 (publish_time, id)，仅用于按最新排序
+#[instrument(skip_all)]
 pub async fn select_posts(
     db: &DatabaseConnection,
-    tag_id: Option<i32>,
-    keyword: Option<String>,
-    is_del: Option<bool>,
-    start: Option<DateTimeWithTimeZone>,
-    end: Option<DateTimeWithTimeZone>,
-    page_size: i32,
-    offset: i32,
+    filter: PostFilter,
+    sort: PostSort,
+    after: Option<(DateTimeWithTimeZone, i32)>,
+    limit: u64,
+    offset: u64,
 ) -> Result<Vec<post::Model>> {
-    let mut select = post::Entity::find();
-    if let Some(tag_id) = tag_id {
-        // 这就是一坨 💩
-        select = select.filter(Expr::cust(format!(
-            "convert_from(tag_id, 'UTF8') LIKE '%X{}X%'",
-            tag_id
-        )));
-    }
-    if let Some(keyword) = keyword {
-        select = select.filter(Column::Title.contains(keyword));
-    }
-    if let Some(is_del) = is_del {
-        select = select.filter(Column::IsDel.eq(is_del));
-    }
-    if start.is_some() && end.is_some() {
-        select = select.filter(Column::PublishTime.between(start, end));
+    let query = filter
+        .keyword
+        .as_deref()
+        .and_then(|keyword| search_query(&search_terms(keyword)));
+    let mut select = filter.apply(post::Entity::find());
+    select = match (sort, query) {
+        (PostSort::Relevance, Some(query)) => {
+            select.order_by_desc(Expr::cust_with_exprs("ts_rank(search_vector, $1)", [query]))
+        }
+        (PostSort::MostHits, _) => select.order_by_desc(Column::Hit),
+        _ => select,
+    };
+    if let Some((publish_time, id)) = after {
+        select = select.filter(
+            Expr::tuple([
+                Expr::col((post::Entity, Column::PublishTime)).into(),
+                Expr::col((post::Entity, Column::Id)).into(),
+            ])
+            .lt(Expr::tuple([
+                Expr::val(publish_time).into(),
+                Expr::val(id).into(),
+            ])),
+        );
     }
     let res = select
+        .order_by_desc(Column::PublishTime)
         .order_by_desc(Column::Id)
-        .limit(Some(page_size as u64))
-        .offset(Some(offset as u64))
+        .limit(Some(limit))
+        .offset(Some(offset))
         .all(db)
         .await?;
     Ok(res)
 }
 
+#[instrument(skip_all)]
 pub async fn update_post_del(db: &DatabaseConnection, id: i32) -> Result<bool> {
     let res = post::Entity::update_many()
         .filter(Column::Id.eq(id))
@@ -130,31 +301,246 @@ pub async fn update_post_del(db: &DatabaseConnection, id: i32) -> Result<bool> {
     }
 }
 
+#[instrument(skip_all)]
 pub async fn select_a_post(
     db: &DatabaseConnection,
     id: i32,
     is_del: Option<bool>,
-    inc_hit: Option<bool>,
+    status: Option<PostStatus>,
 ) -> Result<Option<post::Model>> {
-    let mut select = post::Entity::update_many().filter(Column::Id.eq(id));
+    let mut select = post::Entity::find_by_id(id);
     if let Some(is_del) = is_del {
         select = select.filter(Column::IsDel.eq(is_del));
     }
-    if inc_hit.unwrap_or(true) {
-        select = select.col_expr(Column::Hit, Expr::col(Column::Hit).add(1));
+    if let Some(status) = status {
+        select = select.filter(status_condition(status));
+    }
+    Ok(select.one(db).await?)
+}
+
+/// add_post_views 批量累加点击量和每天的浏览量，views 为 (文章 id, 日期) -> 浏览量，
+/// 不存在的文章直接丢弃，避免外键约束失败导致整批一直写不进去
+#[instrument(skip_all)]
+pub async fn add_post_views(
+    db: &DatabaseConnection,
+    views: &HashMap<(i32, NaiveDate), i32>,
+) -> Result<()> {
+    if views.is_empty() {
+        return Ok(());
+    }
+    let mut hits: HashMap<i32, i32> = HashMap::new();
+    for (&(post_id, _), &count) in views {
+        *hits.entry(post_id).or_default() += count;
+    }
+    let txn = db.begin().await?;
+    let exists = post::Entity::find()
+        .select_only()
+        .column(Column::Id)
+        .filter(Column::Id.is_in(hits.keys().copied()))
+        .into_tuple::<i32>()
+        .all(&txn)
+        .await?;
+    hits.retain(|post_id, _| exists.contains(post_id));
+    if hits.is_empty() {
+        return Ok(());
+    }
+    for (&post_id, &count) in &hits {
+        post::Entity::update_many()
+            .filter(Column::Id.eq(post_id))
+            .col_expr(Column::Hit, Expr::col(Column::Hit).add(count))
+            .exec(&txn)
+            .await?;
+    }
+    let models = views
+        .iter()
+        .filter(|((post_id, _), _)| hits.contains_key(post_id))
+        .map(|(&(post_id, day), &count)| post_view_daily::ActiveModel {
+            post_id: Set(post_id),
+            day: Set(day),
+            views: Set(count),
+        });
+    post_view_daily::Entity::insert_many(models)
+        .on_conflict(
+            OnConflict::columns([post_view_daily::Column::PostId, post_view_daily::Column::Day])
+                .value(
+                    post_view_daily::Column::Views,
+                    Expr::col((post_view_daily::Entity, post_view_daily::Column::Views))
+                        .add(Expr::cust("EXCLUDED.views")),
+                )
+                .to_owned(),
+        )
+        .exec(&txn)
+        .await?;
+    txn.commit().await?;
+    Ok(())
+}
+
+/// select_sitemap_entries 已发布文章的 id 和最后修改时间，按 id 从小到大
+#[instrument(skip_all)]
+pub async fn select_sitemap_entries(
+    db: &DatabaseConnection,
+    limit: u64,
+    offset: u64,
+) -> Result<Vec<(i32, DateTimeWithTimeZone)>> {
+    let res = post::Entity::find()
+        .select_only()
+        .column(Column::Id)
+        .column_as(
+            SimpleExpr::from(Func::coalesce([
+                Expr::col(Column::UpdateTime).into(),
+                Expr::col(Column::PublishTime).into(),
+            ])),
+            "lastmod",
+        )
+        .filter(Column::IsDel.eq(false))
+        .filter(status_condition(PostStatus::Published))
+        .order_by_asc(Column::Id)
+        .limit(limit)
+        .offset(offset)
+        .into_tuple()
+        .all(db)
+        .await?;
+    Ok(res)
+}
+
+/// select_revisions 查询文章的修订记录，从新到旧
+#[instrument(skip_all)]
+pub async fn select_revisions(
+    db: &DatabaseConnection,
+    post_id: i32,
+) -> Result<Vec<post_revision::Model>> {
+    let res = post_revision::Entity::find()
+        .filter(post_revision::Column::PostId.eq(post_id))
+        .order_by_desc(post_revision::Column::Id)
+        .all(db)
+        .await?;
+    Ok(res)
+}
+
+#[instrument(skip_all)]
+pub async fn select_revision(
+    db: &DatabaseConnection,
+    id: i32,
+) -> Result<Option<post_revision::Model>> {
+    Ok(post_revision::Entity::find_by_id(id).one(db).await?)
+}
+
+/// select_daily_views 区间内每天的浏览量，post_id 为 None 时统计全站
+#[instrument(skip_all)]
+pub async fn select_daily_views(
+    db: &DatabaseConnection,
+    post_id: Option<i32>,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<HashMap<NaiveDate, i64>> {
+    let mut select = post_view_daily::Entity::find()
+        .select_only()
+        .column(post_view_daily::Column::Day)
+        .column_as(post_view_daily::Column::Views.sum(), "views")
+        .filter(post_view_daily::Column::Day.between(start, end))
+        .group_by(post_view_daily::Column::Day);
+    if let Some(post_id) = post_id {
+        select = select.filter(post_view_daily::Column::PostId.eq(post_id));
     }
-    let res = select.exec_with_returning(db).await?;
-    Ok(res.first().map(|model| model.to_owned()))
+    let res = select.into_tuple::<(NaiveDate, i64)>().all(db).await?;
+    Ok(res.into_iter().collect())
+}
+
+/// select_top_posts 区间内浏览量最多的未删除文章，返回 (id, 标题, 浏览量)
+#[instrument(skip_all)]
+pub async fn select_top_posts(
+    db: &DatabaseConnection,
+    start: NaiveDate,
+    end: NaiveDate,
+    limit: u64,
+) -> Result<Vec<(i32, String, i64)>> {
+    let views = post_view_daily::Column::Views.sum();
+    let res = post_view_daily::Entity::find()
+        .select_only()
+        .column(post_view_daily::Column::PostId)
+        .column(Column::Title)
+        .column_as(views.clone(), "views")
+        .join(JoinType::InnerJoin, post_view_daily::Relation::Post.def())
+        .filter(post_view_daily::Column::Day.between(start, end))
+        .filter(Column::IsDel.eq(false))
+        .group_by(post_view_daily::Column::PostId)
+        .group_by(Column::Title)
+        .order_by_desc(views)
+        .order_by_asc(post_view_daily::Column::PostId)
+        .limit(limit)
+        .into_tuple()
+        .all(db)
+        .await?;
+    Ok(res)
+}
+
+/// select_tag_post_counts 每个未删除标签下已发布且未删除的文章数，返回 (标签 id, 标签名, 文章数)
+#[instrument(skip_all)]
+pub async fn select_tag_post_counts(db: &DatabaseConnection) -> Result<Vec<(i32, String, i64)>> {
+    let posts = Query::select()
+        .expr(Func::count(Expr::col((post::Entity, Column::Id))))
+        .from(post_tag::Entity)
+        .inner_join(
+            post::Entity,
+            Expr::col((post::Entity, Column::Id))
+                .equals((post_tag::Entity, post_tag::Column::PostId)),
+        )
+        .and_where(
+            Expr::col((post_tag::Entity, post_tag::Column::TagId))
+                .equals((tag::Entity, tag::Column::Id)),
+        )
+        .and_where(Expr::col((post::Entity, Column::IsDel)).eq(false))
+        .cond_where(status_condition(PostStatus::Published))
+        .to_owned();
+    let posts = SimpleExpr::SubQuery(None, Box::new(posts.into_sub_query_statement()));
+    let res = tag::Entity::find()
+        .select_only()
+        .column(tag::Column::Id)
+        .column(tag::Column::Name)
+        .column_as(posts.clone(), "posts")
+        .filter(tag::Column::IsDel.eq(false))
+        .order_by_desc(posts)
+        .order_by_asc(tag::Column::Id)
+        .into_tuple()
+        .all(db)
+        .await?;
+    Ok(res)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::dbaccess::select_record_total;
+    use crate::dbaccess::{add_post_views, select_record_total, PostFilter};
+    use entity::entity::post_tag;
+    use sea_orm::sqlx::types::chrono::Local;
+    use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter};
+    use std::collections::HashMap;
 
     #[tokio::test]
     async fn test_select_record_total() {
         let db = util::get_db_connection().await.unwrap();
-        let res = select_record_total(&db, Some(2), None, None, None, None).await;
-        dbg!(res.unwrap());
+        let filter = PostFilter {
+            tag_id: vec![2],
+            match_all_tags: false,
+            keyword: None,
+            is_del: None,
+            start: None,
+            end: None,
+            status: None,
+        };
+        let res = select_record_total(&db, filter).await.unwrap();
+        // 每篇文章和标签的关联只有一条，带有标签 2 的文章数等于关联数
+        let expected = post_tag::Entity::find()
+            .filter(post_tag::Column::TagId.eq(2))
+            .count(&db)
+            .await
+            .unwrap();
+        assert_eq!(res, expected);
+    }
+
+    #[tokio::test]
+    async fn test_add_post_views_unknown_post() {
+        let db = util::get_db_connection().await.unwrap();
+        let views = HashMap::from([((i32::MAX, Local::now().date_naive()), 1)]);
+        add_post_views(&db, &views).await.unwrap();
     }
 }
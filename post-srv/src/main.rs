@@ -1,16 +1,24 @@
+use std::sync::Arc;
+
 use proto::post_service_server::PostServiceServer;
-use util::{get_db_connection, get_service_addr};
+use util::health::health_service;
+use util::interceptor::CheckServiceToken;
+use util::metrics::serve_metrics;
+use util::{
+    apply_pending_migrations, get_db_connection, get_service_addr, grpc_server, shutdown_signal,
+};
 
 mod dbaccess;
+mod search;
 mod server;
+mod views;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    tracing_subscriber::fmt()
-        .with_target(false)
-        .compact()
-        .init();
-
+    let config = util::telemetry::init(util::Service::Post).map_err(|err| {
+        tracing::error!("{}", err);
+        err
+    })?;
     let addr = get_service_addr(util::Service::Post).map_err(|err| {
         tracing::error!("{}", err);
         err
@@ -19,18 +27,51 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         tracing::error!("{}", err);
         err
     })?;
+    apply_pending_migrations(&db).await.map_err(|err| {
+        tracing::error!("{}", err);
+        err
+    })?;
+    let auth = CheckServiceToken::from_config().map_err(|err| {
+        tracing::error!("{}", err);
+        err
+    })?;
+
+    let views = Arc::new(views::ViewCounter::new(config.views.dedup_window()));
+    views
+        .clone()
+        .spawn_flush(db.clone(), config.views.flush_interval());
+
+    let health = health_service::<PostServiceServer<server::Post>>(
+        db.clone(),
+        config.database.health_check_interval(),
+    );
+    let post_service = server::Post::new(db.clone(), views.clone());
 
-    let post_service = server::Post::new(db);
+    serve_metrics(util::Service::Post).await.map_err(|err| {
+        tracing::error!("{}", err);
+        err
+    })?;
+    let mut grpc = grpc_server(util::Service::Post).map_err(|err| {
+        tracing::error!("{}", err);
+        err
+    })?;
 
     tracing::info!("Post Service runs at: {}", addr);
-    tonic::transport::Server::builder()
-        .add_service(PostServiceServer::new(post_service))
-        .serve(addr.parse()?)
+    grpc
+        .add_service(health)
+        .add_service(PostServiceServer::with_interceptor(post_service, auth))
+        .serve_with_shutdown(addr.parse()?, shutdown_signal())
         .await
         .map_err(|err| {
             tracing::error!("{}", err);
             err
         })?;
 
+    // 退出前写入尚未保存的浏览量
+    if let Err(err) = views.flush(&db).await {
+        tracing::error!("Failed to flush post views: {}", err);
+    }
+
+    util::telemetry::shutdown().await;
     Ok(())
 }
@@ -0,0 +1,139 @@
+// 全文搜索：生成 tsquery 以及关键字高亮
+
+use proto::SearchHighlight;
+use sea_orm::prelude::Expr;
+use sea_orm::sea_query::SimpleExpr;
+
+const SNIPPET_LEN: usize = 120; // 片段字数
+const SNIPPET_BEFORE: usize = 30; // 片段中关键字之前保留的字数
+
+/// search_terms 按空白拆分关键字
+pub fn search_terms(keyword: &str) -> Vec<String> {
+    keyword.split_whitespace().map(str::to_owned).collect()
+}
+
+/// search_query 每个词按短语匹配，多个词需同时匹配，与 migration 中的 search_split 配合
+pub fn search_query(terms: &[String]) -> Option<SimpleExpr> {
+    if terms.is_empty() {
+        return None;
+    }
+    let sql = (1..=terms.len())
+        .map(|i| format!("phraseto_tsquery('simple', search_split(${}))", i))
+        .collect::<Vec<String>>()
+        .join(" && ");
+    Some(Expr::cust_with_values(sql, terms.to_owned()))
+}
+
+/// highlight 标题和正文片段中的关键字用 <mark> 包裹
+pub fn highlight(title: &str, md_content: &str, terms: &[String]) -> SearchHighlight {
+    let text = plain_text(md_content);
+    let chars = text.char_indices().map(|(i, _)| i).collect::<Vec<usize>>();
+    let start = terms
+        .iter()
+        .filter_map(|term| find_ignore_case(&text, term, 0))
+        .min()
+        .map(|pos| {
+            chars
+                .partition_point(|&i| i < pos)
+                .saturating_sub(SNIPPET_BEFORE)
+        })
+        .unwrap_or(0);
+    let end = (start + SNIPPET_LEN).min(chars.len());
+    let byte_start = chars.get(start).copied().unwrap_or(text.len());
+    let byte_end = chars.get(end).copied().unwrap_or(text.len());
+
+    let mut snippet = String::new();
+    if start > 0 {
+        snippet.push('…');
+    }
+    snippet.push_str(&mark(&text[byte_start..byte_end], terms));
+    if end < chars.len() {
+        snippet.push('…');
+    }
+    SearchHighlight {
+        title: mark(title, terms),
+        snippet,
+    }
+}
+
+/// plain_text 去掉 markdown 符号并合并空白
+fn plain_text(md: &str) -> String {
+    md.split_whitespace()
+        .map(|word| word.trim_matches(|c| matches!(c, '*' | '#' | '_' | '>' | '`' | '-' | '|')))
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+/// find_ignore_case 从 from 开始查找 needle，忽略 ASCII 大小写，返回字节位置
+fn find_ignore_case(haystack: &str, needle: &str, from: usize) -> Option<usize> {
+    haystack[from..]
+        .char_indices()
+        .map(|(i, _)| from + i)
+        .find(|&i| {
+            haystack
+                .get(i..i + needle.len())
+                .is_some_and(|s| s.eq_ignore_ascii_case(needle))
+        })
+}
+
+fn mark(text: &str, terms: &[String]) -> String {
+    let mut res = String::new();
+    let mut pos = 0;
+    while pos < text.len() {
+        // 取最先出现的关键字，位置相同时取较长的
+        let next = terms
+            .iter()
+            .filter_map(|term| find_ignore_case(text, term, pos).map(|i| (i, term.len())))
+            .min_by_key(|&(i, len)| (i, usize::MAX - len));
+        match next {
+            Some((i, len)) => {
+                res.push_str(&escape_html(&text[pos..i]));
+                res.push_str("<mark>");
+                res.push_str(&escape_html(&text[i..i + len]));
+                res.push_str("</mark>");
+                pos = i + len;
+            }
+            None => {
+                res.push_str(&escape_html(&text[pos..]));
+                break;
+            }
+        }
+    }
+    res
+}
+
+fn escape_html(text: &str) -> String {
+    let mut res = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => res.push_str("&amp;"),
+            '<' => res.push_str("&lt;"),
+            '>' => res.push_str("&gt;"),
+            '"' => res.push_str("&quot;"),
+            '\'' => res.push_str("&#39;"),
+            c => res.push(c),
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::search::{highlight, search_terms};
+
+    #[test]
+    fn test_highlight() {
+        let terms = search_terms("rust 微服务");
+        let res = highlight(
+            "Rust 微服务博客",
+            "# Lily Blog\n\n一个使用 **Rust** 的微服务的博客 a&b",
+            &terms,
+        );
+        assert_eq!(res.title, "<mark>Rust</mark> <mark>微服务</mark>博客");
+        assert_eq!(
+            res.snippet,
+            "Lily Blog 一个使用 <mark>Rust</mark> 的<mark>微服务</mark>的博客 a&amp;b"
+        );
+    }
+}
@@ -1,24 +1,43 @@
 use std::sync::Arc;
 
 use crate::dbaccess::*;
+use crate::search::{highlight, search_terms};
+use crate::views::ViewCounter;
+use entity::entity::sea_orm_active_enums::PostStatus;
 use proto::post_service_server::PostService;
 use proto::{
-    CreatePostReply, CreatePostRequest, EditPostReply, EditPostRequest, GetPostReply,
-    GetPostRequest, ListPostReply, ListPostRequest, TogglePostReply, TogglePostRequest,
+    diff_line, CountTagPostsReply, CountTagPostsRequest, CreatePostReply, CreatePostRequest,
+    DailyViews, DiffLine, DiffPostRevisionsReply, DiffPostRevisionsRequest, EditPostReply,
+    EditPostRequest, GetDailyViewsReply, GetDailyViewsRequest, GetPostReply, GetPostRequest,
+    ListPostReply, ListPostRequest, ListPostRevisionsReply, ListPostRevisionsRequest,
+    ListPostSitemapReply, ListPostSitemapRequest, ListTopPostsReply, ListTopPostsRequest,
+    RecordPostViewReply, RecordPostViewRequest, RestorePostRevisionReply,
+    RestorePostRevisionRequest, SitemapEntry, TagPostCount, TogglePostReply, TogglePostRequest,
+    TopPost,
 };
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::sqlx::types::chrono::Local;
 use sea_orm::DatabaseConnection;
+use similar::{ChangeTag, TextDiff};
 use tonic::{Request, Response, Status};
+use util::cursor::Cursor;
+use util::stats::{date_range, fill_days};
 
 const PAGE_SIZE: i32 = 10;
+const MAX_PAGE_SIZE: i32 = 100;
+const TOP_POSTS_LIMIT: i32 = 10;
+const TOP_POSTS_MAX_LIMIT: i32 = 100;
 
 pub struct Post {
     db_conn: Arc<DatabaseConnection>,
+    views: Arc<ViewCounter>,
 }
 
 impl Post {
-    pub fn new(db_conn: DatabaseConnection) -> Self {
+    pub fn new(db_conn: DatabaseConnection, views: Arc<ViewCounter>) -> Self {
         Self {
             db_conn: Arc::new(db_conn),
+            views,
         }
     }
 }
@@ -32,16 +51,28 @@ impl PostService for Post {
         let CreatePostRequest {
             title,
             tag_id,
-            md_path,
             summary,
+            content,
+            editor_id,
+            status,
+            publish_time,
         } = request.into_inner();
-        let summary = match summary {
-            None => util::get_summary(&md_path).map_err(|err| Status::internal(err.to_string()))?,
-            Some(s) => s,
-        };
-        let res = insert_new_post(&self.db_conn, title, tag_id, md_path, summary)
-            .await
-            .map_err(|err| Status::internal(err.to_string()))?;
+        let status = status.map(status_from_proto).transpose()?;
+        let publish_time = util::timestamp_conversion(publish_time);
+        check_publish_time(status.as_ref(), publish_time)?;
+        let summary = summary.unwrap_or_else(|| util::get_summary(&content));
+        let res = insert_new_post(
+            &self.db_conn,
+            title,
+            tag_id,
+            content,
+            summary,
+            editor_id,
+            status.unwrap_or(PostStatus::Published),
+            publish_time,
+        )
+        .await
+        .map_err(|err| Status::internal(err.to_string()))?;
         Ok(Response::new(CreatePostReply { id: res }))
     }
 
@@ -50,18 +81,26 @@ impl PostService for Post {
         request: Request<EditPostRequest>,
     ) -> Result<Response<EditPostReply>, Status> {
         let r = request.into_inner();
-        let summary = match r.summary {
-            None => {
-                util::get_summary(&r.md_path).map_err(|err| Status::internal(err.to_string()))?
-            }
-            Some(s) => s,
-        };
-        let res = update_post(&self.db_conn, r.id, r.title, r.tag_id, r.md_path, summary)
-            .await
-            .map_err(|err| Status::internal(err.to_string()))?;
+        let status = r.status.map(status_from_proto).transpose()?;
+        let publish_time = util::timestamp_conversion(r.publish_time);
+        check_publish_time(status.as_ref(), publish_time)?;
+        let summary = r.summary.unwrap_or_else(|| util::get_summary(&r.content));
+        let res = update_post(
+            &self.db_conn,
+            r.id,
+            r.title,
+            r.tag_id,
+            r.content,
+            summary,
+            r.editor_id,
+            status,
+            publish_time,
+        )
+        .await
+        .map_err(|err| Status::internal(err.to_string()))?;
         Ok(Response::new(EditPostReply {
             id: r.id,
-            ok: res > 0,
+            ok: res.is_some(),
         }))
     }
 
@@ -75,9 +114,47 @@ impl PostService for Post {
             keyword,
             is_del,
             dateline_range,
+            match_all_tags,
+            status,
+            sort,
+            with_content,
+            cursor,
+            page_size,
+            with_total,
         } = request.into_inner();
-        let page = page.unwrap_or(0);
-        let offset = PAGE_SIZE * page;
+        let status = status.map(status_from_proto).transpose()?;
+        let keyword = keyword.filter(|keyword| !keyword.trim().is_empty());
+        let sort = match sort.map(proto::PostSort::try_from) {
+            Some(Ok(proto::PostSort::Newest)) => PostSort::Newest,
+            Some(Ok(proto::PostSort::MostHits)) => PostSort::MostHits,
+            Some(Ok(proto::PostSort::Relevance)) => PostSort::Relevance,
+            None if keyword.is_some() => PostSort::Relevance,
+            None => PostSort::Newest,
+            Some(Err(_)) => return Err(Status::invalid_argument("unknown sort")),
+        };
+        let page_size = page_size.unwrap_or(PAGE_SIZE);
+        if page_size <= 0 || page_size > MAX_PAGE_SIZE {
+            return Err(Status::invalid_argument("page_size out of range"));
+        }
+        let cursor = match cursor.map(|cursor| Cursor::decode(&cursor)) {
+            Some(None) => return Err(Status::invalid_argument("invalid cursor")),
+            cursor => cursor.flatten(),
+        };
+        // 有游标时从游标处继续，否则按页码
+        let (page, after, offset) = match cursor {
+            Some(Cursor::After {
+                newest: true,
+                time,
+                id,
+            }) if matches!(sort, PostSort::Newest) => (0, Some((time, id)), 0),
+            Some(Cursor::Offset(offset)) if !matches!(sort, PostSort::Newest) => (0, None, offset),
+            Some(_) => return Err(Status::invalid_argument("cursor does not match sort")),
+            None => {
+                let page = page.unwrap_or(0).max(0);
+                (page, None, page as u64 * page_size as u64)
+            }
+        };
+        let match_all_tags = match_all_tags.unwrap_or(false);
         let (start, end) = if let Some(dr) = dateline_range {
             (
                 util::timestamp_conversion(dr.start),
@@ -86,36 +163,77 @@ impl PostService for Post {
         } else {
             (None, None)
         };
-        let record_total =
-            select_record_total(&self.db_conn, tag_id, keyword.clone(), is_del, start, end)
+        let filter = || PostFilter {
+            tag_id: tag_id.clone(),
+            match_all_tags,
+            keyword: keyword.clone(),
+            is_del,
+            start,
+            end,
+            status: status.clone(),
+        };
+        let (total, page_total) = if with_total.unwrap_or(false) {
+            let record_total = select_record_total(&self.db_conn, filter())
                 .await
                 .map_err(|err| Status::internal(err.to_string()))?;
-        let page_total = f64::ceil(record_total as f64 / PAGE_SIZE as f64) as i32;
+            let page_total = f64::ceil(record_total as f64 / page_size as f64) as i32;
+            (Some(record_total as i64), page_total)
+        } else {
+            (None, 0)
+        };
 
-        let res = select_posts(
+        // 多查一篇用于判断是否还有下一页
+        let is_newest = matches!(sort, PostSort::Newest);
+        let mut res = select_posts(
             &self.db_conn,
-            tag_id,
-            keyword,
-            is_del,
-            start,
-            end,
-            PAGE_SIZE,
+            filter(),
+            sort,
+            after,
+            page_size as u64 + 1,
             offset,
         )
         .await
         .map_err(|err| Status::internal(err.to_string()))?;
-        if res.is_empty() {
-            return Err(Status::not_found("no such posts"));
-        }
+        let next_cursor = if res.len() > page_size as usize {
+            res.truncate(page_size as usize);
+            let cursor = match res.last() {
+                Some(last) if is_newest => Cursor::After {
+                    newest: true,
+                    time: last.publish_time,
+                    id: last.id,
+                },
+                _ => Cursor::Offset(offset + page_size as u64),
+            };
+            Some(cursor.encode())
+        } else {
+            None
+        };
 
+        let ids = res.iter().map(|post| post.id).collect::<Vec<i32>>();
+        let mut tags = select_post_tags(&self.db_conn, &ids)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+        let terms = keyword.as_deref().map(search_terms).unwrap_or_default();
         let posts = res
             .iter()
-            .map(model_to_post)
+            .map(|post| {
+                let mut res = model_to_post(
+                    post,
+                    tags.remove(&post.id).unwrap_or_default(),
+                    with_content.unwrap_or(false),
+                );
+                if !terms.is_empty() {
+                    res.highlight = Some(highlight(&post.title, &post.md_content, &terms));
+                }
+                res
+            })
             .collect::<Vec<proto::Post>>();
         Ok(Response::new(ListPostReply {
             page,
             page_total,
             posts,
+            next_cursor,
+            total,
         }))
     }
 
@@ -138,27 +256,328 @@ impl PostService for Post {
             id,
             is_del,
             inc_hit,
+            status,
+            visitor,
         } = request.into_inner();
-        let post = select_a_post(&self.db_conn, id, is_del, inc_hit)
+        let status = status.map(status_from_proto).transpose()?;
+        let post = select_a_post(&self.db_conn, id, is_del, status)
             .await
             .map_err(|err| Status::internal(err.to_string()))?;
-        let post = post.map(|post| model_to_post(&post));
+        let post = match post {
+            Some(mut post) => {
+                // 点击量异步写入，返回的点击量加上还没有写入的部分
+                if inc_hit.unwrap_or(true) {
+                    self.views.record(post.id, visitor);
+                }
+                post.hit += self.views.pending(post.id);
+                let mut tags = select_post_tags(&self.db_conn, &[post.id])
+                    .await
+                    .map_err(|err| Status::internal(err.to_string()))?;
+                Some(model_to_post(
+                    &post,
+                    tags.remove(&post.id).unwrap_or_default(),
+                    true,
+                ))
+            }
+            None => None,
+        };
         Ok(Response::new(GetPostReply { post }))
     }
+
+    async fn list_post_revisions(
+        &self,
+        request: Request<ListPostRevisionsRequest>,
+    ) -> Result<Response<ListPostRevisionsReply>, Status> {
+        let ListPostRevisionsRequest { post_id } = request.into_inner();
+        let res = select_revisions(&self.db_conn, post_id)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+        let revisions = res
+            .iter()
+            .map(|revision| model_to_revision(revision, false))
+            .collect::<Vec<proto::PostRevision>>();
+        Ok(Response::new(ListPostRevisionsReply { revisions }))
+    }
+
+    async fn diff_post_revisions(
+        &self,
+        request: Request<DiffPostRevisionsRequest>,
+    ) -> Result<Response<DiffPostRevisionsReply>, Status> {
+        let DiffPostRevisionsRequest { from_id, to_id } = request.into_inner();
+        let from = select_revision(&self.db_conn, from_id)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?
+            .ok_or(Status::not_found("no such revision"))?;
+        let to = select_revision(&self.db_conn, to_id)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?
+            .ok_or(Status::not_found("no such revision"))?;
+        if from.post_id != to.post_id {
+            return Err(Status::invalid_argument(
+                "revisions belong to different posts",
+            ));
+        }
+        let lines = diff_lines(&from.md_content, &to.md_content);
+        Ok(Response::new(DiffPostRevisionsReply {
+            from: Some(model_to_revision(&from, true)),
+            to: Some(model_to_revision(&to, true)),
+            lines,
+        }))
+    }
+
+    async fn restore_post_revision(
+        &self,
+        request: Request<RestorePostRevisionRequest>,
+    ) -> Result<Response<RestorePostRevisionReply>, Status> {
+        let RestorePostRevisionRequest {
+            post_id,
+            revision_id,
+            editor_id,
+        } = request.into_inner();
+        let revision = select_revision(&self.db_conn, revision_id)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?
+            .ok_or(Status::not_found("no such revision"))?;
+        if revision.post_id != post_id {
+            return Err(Status::invalid_argument(
+                "revision does not belong to the post",
+            ));
+        }
+        // 恢复也作为一次修改，会产生新的修订
+        let res = update_post(
+            &self.db_conn,
+            post_id,
+            revision.title,
+            revision.tag_id,
+            revision.md_content,
+            revision.summary,
+            editor_id,
+            None,
+            None,
+        )
+        .await
+        .map_err(|err| Status::internal(err.to_string()))?;
+        Ok(Response::new(RestorePostRevisionReply {
+            id: post_id,
+            ok: res.is_some(),
+            revision_id: res.unwrap_or_default(),
+        }))
+    }
+
+    async fn list_post_sitemap(
+        &self,
+        request: Request<ListPostSitemapRequest>,
+    ) -> Result<Response<ListPostSitemapReply>, Status> {
+        let ListPostSitemapRequest { page, page_size } = request.into_inner();
+        if page_size <= 0 || page_size > util::SITEMAP_MAX_URLS {
+            return Err(Status::invalid_argument("page_size out of range"));
+        }
+        let page = page.unwrap_or(0).max(0);
+        let filter = PostFilter {
+            tag_id: vec![],
+            match_all_tags: false,
+            keyword: None,
+            is_del: Some(false),
+            start: None,
+            end: None,
+            status: Some(PostStatus::Published),
+        };
+        let total = select_record_total(&self.db_conn, filter)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+        let res = select_sitemap_entries(
+            &self.db_conn,
+            page_size as u64,
+            page as u64 * page_size as u64,
+        )
+        .await
+        .map_err(|err| Status::internal(err.to_string()))?;
+        let entries = res
+            .into_iter()
+            .map(|(id, lastmod)| SitemapEntry {
+                id,
+                lastmod: util::datetime_conversion(Some(lastmod)),
+            })
+            .collect::<Vec<SitemapEntry>>();
+        Ok(Response::new(ListPostSitemapReply {
+            total: total as i64,
+            entries,
+        }))
+    }
+
+    async fn get_daily_views(
+        &self,
+        request: Request<GetDailyViewsRequest>,
+    ) -> Result<Response<GetDailyViewsReply>, Status> {
+        let GetDailyViewsRequest {
+            post_id,
+            start,
+            end,
+        } = request.into_inner();
+        let (start, end) = date_range(&start, &end).map_err(Status::invalid_argument)?;
+        let res = select_daily_views(&self.db_conn, post_id, start, end)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+        let days = fill_days(start, end, &res)
+            .into_iter()
+            .map(|(day, views)| DailyViews { day, views })
+            .collect::<Vec<DailyViews>>();
+        Ok(Response::new(GetDailyViewsReply { days }))
+    }
+
+    async fn list_top_posts(
+        &self,
+        request: Request<ListTopPostsRequest>,
+    ) -> Result<Response<ListTopPostsReply>, Status> {
+        let ListTopPostsRequest { start, end, limit } = request.into_inner();
+        let (start, end) = date_range(&start, &end).map_err(Status::invalid_argument)?;
+        let limit = limit.unwrap_or(TOP_POSTS_LIMIT);
+        if limit <= 0 || limit > TOP_POSTS_MAX_LIMIT {
+            return Err(Status::invalid_argument("limit out of range"));
+        }
+        let res = select_top_posts(&self.db_conn, start, end, limit as u64)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+        let posts = res
+            .into_iter()
+            .map(|(id, title, views)| TopPost { id, title, views })
+            .collect::<Vec<TopPost>>();
+        Ok(Response::new(ListTopPostsReply { posts }))
+    }
+
+    async fn count_tag_posts(
+        &self,
+        _request: Request<CountTagPostsRequest>,
+    ) -> Result<Response<CountTagPostsReply>, Status> {
+        let res = select_tag_post_counts(&self.db_conn)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+        let tags = res
+            .into_iter()
+            .map(|(tag_id, name, posts)| TagPostCount {
+                tag_id,
+                name,
+                posts,
+            })
+            .collect::<Vec<TagPostCount>>();
+        Ok(Response::new(CountTagPostsReply { tags }))
+    }
+
+    async fn record_post_view(
+        &self,
+        request: Request<RecordPostViewRequest>,
+    ) -> Result<Response<RecordPostViewReply>, Status> {
+        let RecordPostViewRequest { post_id, visitor } = request.into_inner();
+        // 只记录公开可见的文章，不存在的 id 写入时会违反外键约束
+        select_a_post(
+            &self.db_conn,
+            post_id,
+            Some(false),
+            Some(PostStatus::Published),
+        )
+        .await
+        .map_err(|err| Status::internal(err.to_string()))?
+        .ok_or(Status::not_found("no such post"))?;
+        let counted = self.views.record(post_id, visitor);
+        Ok(Response::new(RecordPostViewReply { counted }))
+    }
 }
 
-fn model_to_post(post: &entity::entity::post::Model) -> proto::Post {
+/// with_content 为 true 时附带文章内容
+fn model_to_post(
+    post: &entity::entity::post::Model,
+    tag_id: Vec<i32>,
+    with_content: bool,
+) -> proto::Post {
     proto::Post {
         id: post.id,
         title: post.title.to_owned(),
-        tag_id: util::u8_to_tags(post.tag_id.to_owned().unwrap_or_default()), // tag_id 在这儿捏
+        tag_id,
         summary: post.summary.to_owned(),
-        md_path: post.md_path.to_owned(),
-        html_path: post.html_path.to_owned(),
         hit: post.hit,
         words_len: post.words_len.unwrap_or(0),
         is_del: post.is_del,
         publish_time: util::datetime_conversion(Some(post.publish_time)),
         update_time: util::datetime_conversion(post.update_time),
+        md_content: with_content.then(|| post.md_content.to_owned()),
+        html_content: with_content.then(|| post.html_content.to_owned()),
+        status: status_to_proto(post) as i32,
+        highlight: None,
+    }
+}
+
+#[allow(clippy::result_large_err)]
+fn status_from_proto(status: i32) -> Result<PostStatus, Status> {
+    let status = proto::PostStatus::try_from(status)
+        .map_err(|_| Status::invalid_argument("unknown post status"))?;
+    Ok(match status {
+        proto::PostStatus::Published => PostStatus::Published,
+        proto::PostStatus::Draft => PostStatus::Draft,
+        proto::PostStatus::Scheduled => PostStatus::Scheduled,
+        proto::PostStatus::Archived => PostStatus::Archived,
+    })
+}
+
+/// check_publish_time 定时发布必须指定 publish_time
+#[allow(clippy::result_large_err)]
+fn check_publish_time(
+    status: Option<&PostStatus>,
+    publish_time: Option<DateTimeWithTimeZone>,
+) -> Result<(), Status> {
+    if status == Some(&PostStatus::Scheduled) && publish_time.is_none() {
+        return Err(Status::invalid_argument(
+            "scheduled post requires publish_time",
+        ));
+    }
+    Ok(())
+}
+
+/// status_to_proto 返回文章当前实际的状态，publish_time 已过的定时文章视为已发布
+fn status_to_proto(post: &entity::entity::post::Model) -> proto::PostStatus {
+    match post.status {
+        PostStatus::Published | PostStatus::Scheduled => {
+            if post.publish_time > Local::now() {
+                proto::PostStatus::Scheduled
+            } else {
+                proto::PostStatus::Published
+            }
+        }
+        PostStatus::Draft => proto::PostStatus::Draft,
+        PostStatus::Archived => proto::PostStatus::Archived,
     }
-}
\ No newline at end of file
+}
+
+/// with_content 为 true 时附带 markdown 原文
+fn model_to_revision(
+    revision: &entity::entity::post_revision::Model,
+    with_content: bool,
+) -> proto::PostRevision {
+    proto::PostRevision {
+        id: revision.id,
+        post_id: revision.post_id,
+        title: revision.title.to_owned(),
+        tag_id: revision.tag_id.to_owned(),
+        summary: revision.summary.to_owned(),
+        md_content: with_content.then(|| revision.md_content.to_owned()),
+        editor_id: revision.editor_id,
+        created_at: util::datetime_conversion(Some(revision.created_at)),
+    }
+}
+
+/// diff_lines 逐行比较两段 markdown
+fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    TextDiff::from_lines(old, new)
+        .iter_all_changes()
+        .map(|change| {
+            let op = match change.tag() {
+                ChangeTag::Equal => diff_line::Op::Equal,
+                ChangeTag::Insert => diff_line::Op::Insert,
+                ChangeTag::Delete => diff_line::Op::Delete,
+            };
+            DiffLine {
+                op: op.into(),
+                content: change.to_string_lossy().trim_end_matches('\n').to_string(),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,160 @@
+// 浏览量统计：同一访客在去重窗口内只计一次，先在内存中累加，再定时批量写入数据库
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use sea_orm::sqlx::types::chrono::{Local, NaiveDate};
+use sea_orm::DatabaseConnection;
+
+use crate::dbaccess::add_post_views;
+
+const MAX_SEEN: usize = 100000; // 访客记录的上限，超过后先清理过期的，仍然太多时丢弃最早的一半
+
+pub struct ViewCounter {
+    window: Duration, // 去重窗口
+    max_seen: usize,
+    state: Mutex<ViewState>,
+}
+
+#[derive(Default)]
+struct ViewState {
+    seen: HashMap<(i32, String), Instant>, // (文章 id, 访客) -> 最近一次计入的时间
+    pending: HashMap<(i32, NaiveDate), i32>, // 还没有写入数据库的浏览量
+}
+
+impl ViewCounter {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            max_seen: MAX_SEEN,
+            state: Mutex::new(ViewState::default()),
+        }
+    }
+
+    /// record 记录一次浏览，没有访客标识时总是计入，返回是否计入
+    pub fn record(&self, post_id: i32, visitor: Option<String>) -> bool {
+        self.record_at(post_id, visitor, Instant::now(), Local::now().date_naive())
+    }
+
+    fn record_at(
+        &self,
+        post_id: i32,
+        visitor: Option<String>,
+        now: Instant,
+        today: NaiveDate,
+    ) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if let Some(visitor) = visitor {
+            match state.seen.get(&(post_id, visitor.clone())) {
+                Some(last) if now.duration_since(*last) < self.window => return false,
+                Some(_) => {
+                    state.seen.insert((post_id, visitor), now);
+                }
+                None => {
+                    if state.seen.len() >= self.max_seen {
+                        state.evict(now, self.window, self.max_seen);
+                    }
+                    state.seen.insert((post_id, visitor), now);
+                }
+            }
+        }
+        *state.pending.entry((post_id, today)).or_default() += 1;
+        true
+    }
+
+    /// pending 文章还没有写入数据库的浏览量
+    pub fn pending(&self, post_id: i32) -> i32 {
+        let state = self.state.lock().unwrap();
+        state
+            .pending
+            .iter()
+            .filter(|((id, _), _)| *id == post_id)
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    /// flush 把累加的浏览量写入数据库，同时清理过期的访客记录，写入失败时保留到下一次
+    pub async fn flush(&self, db: &DatabaseConnection) -> anyhow::Result<()> {
+        let pending = {
+            let mut state = self.state.lock().unwrap();
+            state.evict(Instant::now(), self.window, self.max_seen);
+            std::mem::take(&mut state.pending)
+        };
+        if let Err(err) = add_post_views(db, &pending).await {
+            let mut state = self.state.lock().unwrap();
+            for (key, count) in pending {
+                *state.pending.entry(key).or_default() += count;
+            }
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// spawn_flush 每隔 interval 写入一次
+    pub fn spawn_flush(self: Arc<Self>, db: DatabaseConnection, interval: Duration) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(err) = self.flush(&db).await {
+                    tracing::error!("Failed to flush post views: {}", err);
+                }
+            }
+        });
+    }
+}
+
+impl ViewState {
+    /// evict 清理过期的访客记录，仍然不少于 max_seen 条时丢弃最早的一半，这些访客再次浏览时会被重复计入
+    fn evict(&mut self, now: Instant, window: Duration, max_seen: usize) {
+        self.seen
+            .retain(|_, last| now.duration_since(*last) < window);
+        if self.seen.len() >= max_seen {
+            let mut times = self.seen.values().copied().collect::<Vec<Instant>>();
+            let middle = times.len() / 2;
+            let (_, &mut cutoff, _) = times.select_nth_unstable(middle);
+            self.seen.retain(|_, last| *last > cutoff);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::views::ViewCounter;
+    use sea_orm::sqlx::types::chrono::NaiveDate;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_record_view() {
+        let counter = ViewCounter::new(Duration::from_secs(60));
+        let now = Instant::now();
+        let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        assert!(counter.record_at(1, Some("a".into()), now, today));
+        // 窗口内同一访客不再计入，其他访客和其他文章不受影响
+        assert!(!counter.record_at(1, Some("a".into()), now + Duration::from_secs(59), today));
+        assert!(counter.record_at(1, Some("b".into()), now, today));
+        assert!(counter.record_at(2, Some("a".into()), now, today));
+        assert!(counter.record_at(1, Some("a".into()), now + Duration::from_secs(60), today));
+        assert!(counter.record_at(1, None, now, today));
+        assert_eq!(counter.pending(1), 4);
+        assert_eq!(counter.pending(2), 1);
+    }
+
+    #[test]
+    fn test_record_view_max_seen() {
+        let mut counter = ViewCounter::new(Duration::from_secs(60));
+        counter.max_seen = 4;
+        let now = Instant::now();
+        let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        for i in 0..10 {
+            let at = now + Duration::from_secs(i);
+            assert!(counter.record_at(1, Some(i.to_string()), at, today));
+            assert!(counter.state.lock().unwrap().seen.len() <= 4);
+        }
+        // 最早的访客已经被丢弃，最近的仍在窗口内
+        let at = now + Duration::from_secs(10);
+        assert!(counter.record_at(1, Some("0".into()), at, today));
+        assert!(!counter.record_at(1, Some("9".into()), at, today));
+    }
+}
@@ -1,19 +1,26 @@
 use proto::post_service_client::PostServiceClient;
-use proto::{CreatePostRequest, DatelineRange, EditPostRequest, GetPostRequest, ListPostRequest, TogglePostRequest};
+use proto::{
+    CountTagPostsRequest, CreatePostRequest, DatelineRange, DiffPostRevisionsRequest,
+    EditPostRequest, GetDailyViewsRequest, GetPostRequest, ListPostRequest,
+    ListPostRevisionsRequest, ListPostSitemapRequest, ListTopPostsRequest, PostSort, PostStatus,
+    RecordPostViewRequest, RestorePostRevisionRequest, TogglePostRequest,
+};
 use sea_orm::prelude::DateTimeWithTimeZone;
 use sea_orm::sqlx::types::chrono::{Local, TimeZone};
 use tonic::Request;
-use util::get_service_url;
+use util::interceptor::connect;
 
 #[tokio::test]
 async fn test_create_post() {
-    let client_url = get_service_url(util::Service::Post).unwrap();
-    let mut client = PostServiceClient::connect(client_url).await.unwrap();
+    let mut client = PostServiceClient::new(connect(util::Service::Post).unwrap());
     let request = Request::new(CreatePostRequest {
         title: "test1".into(),
         tag_id: vec![2, 3, 4],
-        md_path: "/Users/cakeal/Desktop/vsc/lily-blog/test_file/test1.md".into(),
         summary: None,
+        content: "# test1\n\nThis is contents of test1.".into(),
+        editor_id: None,
+        status: None,
+        publish_time: None,
     });
     let response = client.create_post(request).await.unwrap();
     dbg!(response.into_inner());
@@ -21,8 +28,11 @@ async fn test_create_post() {
     let request = Request::new(CreatePostRequest {
         title: "test2".into(),
         tag_id: vec![3, 4],
-        md_path: "/Users/cakeal/Desktop/vsc/lily-blog/test_file/test1.md".into(),
         summary: Some("this is a summary".into()),
+        content: "# test2\n\nThis is contents of test2.".into(),
+        editor_id: None,
+        status: None,
+        publish_time: None,
     });
     let response = client.create_post(request).await.unwrap();
     dbg!(response.into_inner());
@@ -30,14 +40,16 @@ async fn test_create_post() {
 
 #[tokio::test]
 async fn test_edit_post() {
-    let client_url = get_service_url(util::Service::Post).unwrap();
-    let mut client = PostServiceClient::connect(client_url).await.unwrap();
+    let mut client = PostServiceClient::new(connect(util::Service::Post).unwrap());
     let request = Request::new(EditPostRequest {
         id: 2,
         title: "test1_edited".to_string(),
         tag_id: vec![2, 3, 4, 5],
-        md_path: "/Users/cakeal/Desktop/vsc/lily-blog/test_file/test1.md".to_string(),
         summary: None,
+        content: "# test1_edited\n\nThis is edited contents of test1.".to_string(),
+        editor_id: None,
+        status: None,
+        publish_time: None,
     });
     let response = client.edit_post(request).await.unwrap();
     dbg!(response.into_inner());
@@ -45,58 +57,109 @@ async fn test_edit_post() {
 
 #[tokio::test]
 async fn test_list_posts() {
-    let client_url = get_service_url(util::Service::Post).unwrap();
-    let mut client = PostServiceClient::connect(client_url).await.unwrap();
+    let mut client = PostServiceClient::new(connect(util::Service::Post).unwrap());
     // 查询全部
     let request = Request::new(ListPostRequest {
         page: None,
-        tag_id: None,
+        tag_id: vec![],
         keyword: None,
         is_del: None,
         dateline_range: None,
+        match_all_tags: None,
+        status: None,
+        sort: None,
+        with_content: None,
+        cursor: None,
+        page_size: None,
+        with_total: None,
     });
     let response = client.list_posts(request).await.unwrap();
     dbg!(response.into_inner());
-    // 查询 tag_id = 2
+    // 查询 tag_id = 4
     let request = Request::new(ListPostRequest {
         page: None,
-        tag_id: Some(4),
+        tag_id: vec![4],
         keyword: None,
         is_del: None,
         dateline_range: None,
+        match_all_tags: None,
+        status: None,
+        sort: None,
+        with_content: None,
+        cursor: None,
+        page_size: None,
+        with_total: None,
+    });
+    let response = client.list_posts(request).await.unwrap();
+    dbg!(response.into_inner());
+    // 查询同时有 tag_id = 3 和 4 的
+    let request = Request::new(ListPostRequest {
+        page: None,
+        tag_id: vec![3, 4],
+        keyword: None,
+        is_del: None,
+        dateline_range: None,
+        match_all_tags: Some(true),
+        status: None,
+        sort: None,
+        with_content: None,
+        cursor: None,
+        page_size: None,
+        with_total: None,
     });
     let response = client.list_posts(request).await.unwrap();
     dbg!(response.into_inner());
     // 查询 keyword = test
     let request = Request::new(ListPostRequest {
         page: None,
-        tag_id: None,
+        tag_id: vec![],
         keyword: Some("test".into()),
         is_del: None,
         dateline_range: None,
+        match_all_tags: None,
+        status: None,
+        sort: None,
+        with_content: None,
+        cursor: None,
+        page_size: None,
+        with_total: None,
     });
     let response = client.list_posts(request).await.unwrap();
     dbg!(response.into_inner());
     // 查询 is_del = true
     let request = Request::new(ListPostRequest {
         page: None,
-        tag_id: None,
+        tag_id: vec![],
         keyword: None,
         is_del: Some(true),
         dateline_range: None,
+        match_all_tags: None,
+        status: None,
+        sort: None,
+        with_content: None,
+        cursor: None,
+        page_size: None,
+        with_total: None,
     });
     let response = client.list_posts(request).await.unwrap();
     dbg!(response.into_inner());
     // 查询 dateline_range = [1723359749, 1723359751]
     let request = Request::new(ListPostRequest {
         page: None,
-        tag_id: None,
+        tag_id: vec![],
         keyword: None,
         is_del: None,
         dateline_range: Some(DatelineRange { 
             start: util::datetime_conversion(Some(DateTimeWithTimeZone::from(Local.timestamp_opt(1723359749, 0).unwrap()))),
             end: util::datetime_conversion(Some(DateTimeWithTimeZone::from(Local.timestamp_opt(1723359751, 0).unwrap()))),
         }),
+        match_all_tags: None,
+        status: None,
+        sort: None,
+        with_content: None,
+        cursor: None,
+        page_size: None,
+        with_total: None,
     });
     let response = client.list_posts(request).await.unwrap();
     dbg!(response.into_inner());
@@ -104,8 +167,7 @@ async fn test_list_posts() {
 
 #[tokio::test]
 async fn test_toggle_post() {
-    let client_url = get_service_url(util::Service::Post).unwrap();
-    let mut client = PostServiceClient::connect(client_url).await.unwrap();
+    let mut client = PostServiceClient::new(connect(util::Service::Post).unwrap());
     let request = Request::new(TogglePostRequest { id: 1 });
     let response = client.toggle_post(request).await.unwrap();
     dbg!(response.into_inner());
@@ -113,18 +175,441 @@ async fn test_toggle_post() {
 
 #[tokio::test]
 async fn get_post() {
-    let client_url = get_service_url(util::Service::Post).unwrap();
-    let mut client = PostServiceClient::connect(client_url).await.unwrap();
+    let mut client = PostServiceClient::new(connect(util::Service::Post).unwrap());
     // 默认增加点击量
-    let request = Request::new(GetPostRequest { id: 1, is_del: None, inc_hit: None });
+    let request = Request::new(GetPostRequest { id: 1, is_del: None, inc_hit: None, status: None, visitor: None });
     let response = client.get_post(request).await.unwrap();
     dbg!(response.into_inner());
     // 设置不增加点击量
-    let request = Request::new(GetPostRequest { id: 2, is_del: None, inc_hit: Some(false) });
+    let request = Request::new(GetPostRequest { id: 2, is_del: None, inc_hit: Some(false), status: None, visitor: None });
     let response = client.get_post(request).await.unwrap();
     dbg!(response.into_inner());
     // 查询 id 3，条件 is_del 为 true，会返回 None，没有这个 post
-    let request = Request::new(GetPostRequest { id: 3, is_del: Some(true), inc_hit: None });
+    let request = Request::new(GetPostRequest { id: 3, is_del: Some(true), inc_hit: None, status: None, visitor: None });
     let response = client.get_post(request).await.unwrap();
     assert_eq!(response.into_inner().post, None);
-}
\ No newline at end of file
+}
+
+#[tokio::test]
+async fn test_post_revisions() {
+    let mut client = PostServiceClient::new(connect(util::Service::Post).unwrap());
+    let request = Request::new(CreatePostRequest {
+        title: "revision".into(),
+        tag_id: vec![2],
+        summary: None,
+        content: "line1\nline2\n".into(),
+        editor_id: None,
+        status: None,
+        publish_time: None,
+    });
+    let id = client.create_post(request).await.unwrap().into_inner().id;
+    let request = Request::new(EditPostRequest {
+        id,
+        title: "revision_edited".into(),
+        tag_id: vec![2, 3],
+        summary: None,
+        content: "line1\nline2 edited\nline3\n".into(),
+        editor_id: None,
+        status: None,
+        publish_time: None,
+    });
+    assert!(client.edit_post(request).await.unwrap().into_inner().ok);
+
+    // 从新到旧，创建和修改各一个修订
+    let request = Request::new(ListPostRevisionsRequest { post_id: id });
+    let revisions = client
+        .list_post_revisions(request)
+        .await
+        .unwrap()
+        .into_inner()
+        .revisions;
+    assert_eq!(revisions.len(), 2);
+    let (new, old) = (revisions[0].id, revisions[1].id);
+
+    let request = Request::new(DiffPostRevisionsRequest {
+        from_id: old,
+        to_id: new,
+    });
+    let diff = client.diff_post_revisions(request).await.unwrap().into_inner();
+    dbg!(&diff.lines);
+    assert_eq!(diff.lines.len(), 4);
+
+    // 恢复到第一个修订
+    let request = Request::new(RestorePostRevisionRequest {
+        post_id: id,
+        revision_id: old,
+        editor_id: None,
+    });
+    let reply = client.restore_post_revision(request).await.unwrap().into_inner();
+    assert!(reply.ok);
+    let request = Request::new(GetPostRequest {
+        id,
+        is_del: None,
+        inc_hit: Some(false),
+        status: None,
+        visitor: None,
+    });
+    let post = client.get_post(request).await.unwrap().into_inner().post.unwrap();
+    assert_eq!(post.title, "revision");
+    assert_eq!(post.tag_id, vec![2]);
+
+    // 不属于该文章的修订
+    let request = Request::new(RestorePostRevisionRequest {
+        post_id: i32::MAX,
+        revision_id: old,
+        editor_id: None,
+    });
+    assert!(client.restore_post_revision(request).await.is_err());
+}
+
+#[tokio::test]
+async fn test_post_status() {
+    let mut client = PostServiceClient::new(connect(util::Service::Post).unwrap());
+    // 定时发布必须指定 publish_time
+    let request = Request::new(CreatePostRequest {
+        title: "scheduled".into(),
+        tag_id: vec![2],
+        summary: None,
+        content: "# scheduled".into(),
+        editor_id: None,
+        status: Some(PostStatus::Scheduled as i32),
+        publish_time: None,
+    });
+    assert!(client.create_post(request).await.is_err());
+
+    let publish_time = util::i64_to_timestamp(Some(Local::now().timestamp() + 3600));
+    let request = Request::new(CreatePostRequest {
+        title: "scheduled".into(),
+        tag_id: vec![2],
+        summary: None,
+        content: "# scheduled".into(),
+        editor_id: None,
+        status: Some(PostStatus::Scheduled as i32),
+        publish_time,
+    });
+    let id = client.create_post(request).await.unwrap().into_inner().id;
+
+    // 未到发布时间，按已发布查询不到
+    let request = Request::new(GetPostRequest {
+        id,
+        is_del: None,
+        inc_hit: Some(false),
+        status: Some(PostStatus::Published as i32),
+        visitor: None,
+    });
+    let response = client.get_post(request).await.unwrap();
+    assert_eq!(response.into_inner().post, None);
+    let request = Request::new(GetPostRequest {
+        id,
+        is_del: None,
+        inc_hit: Some(false),
+        status: None,
+        visitor: None,
+    });
+    let post = client.get_post(request).await.unwrap().into_inner().post.unwrap();
+    assert_eq!(post.status(), PostStatus::Scheduled);
+
+    // 修改时同样要求定时发布指定 publish_time
+    let request = Request::new(EditPostRequest {
+        id,
+        title: "scheduled".into(),
+        tag_id: vec![2],
+        summary: None,
+        content: "# scheduled".into(),
+        editor_id: None,
+        status: Some(PostStatus::Scheduled as i32),
+        publish_time: None,
+    });
+    assert!(client.edit_post(request).await.is_err());
+
+    // 把发布时间改到过去后视为已发布
+    let request = Request::new(EditPostRequest {
+        id,
+        title: "scheduled".into(),
+        tag_id: vec![2],
+        summary: None,
+        content: "# scheduled".into(),
+        editor_id: None,
+        status: None,
+        publish_time: util::i64_to_timestamp(Some(Local::now().timestamp() - 60)),
+    });
+    assert!(client.edit_post(request).await.unwrap().into_inner().ok);
+    let request = Request::new(GetPostRequest {
+        id,
+        is_del: None,
+        inc_hit: Some(false),
+        status: Some(PostStatus::Published as i32),
+        visitor: None,
+    });
+    let post = client.get_post(request).await.unwrap().into_inner().post.unwrap();
+    assert_eq!(post.status(), PostStatus::Published);
+
+    // 草稿不出现在已发布列表中
+    let request = Request::new(EditPostRequest {
+        id,
+        title: "scheduled".into(),
+        tag_id: vec![2],
+        summary: None,
+        content: "# scheduled".into(),
+        editor_id: None,
+        status: Some(PostStatus::Draft as i32),
+        publish_time: None,
+    });
+    assert!(client.edit_post(request).await.unwrap().into_inner().ok);
+    let request = Request::new(ListPostRequest {
+        page: None,
+        tag_id: vec![2],
+        keyword: Some("scheduled".into()),
+        is_del: None,
+        dateline_range: None,
+        match_all_tags: None,
+        status: Some(PostStatus::Published as i32),
+        sort: None,
+        with_content: None,
+        cursor: None,
+        page_size: None,
+        with_total: None,
+    });
+    let response = client.list_posts(request).await;
+    assert!(response.map_or(true, |r| r.into_inner().posts.iter().all(|p| p.id != id)));
+}
+
+#[tokio::test]
+async fn test_search_posts() {
+    let mut client = PostServiceClient::new(connect(util::Service::Post).unwrap());
+    let request = Request::new(CreatePostRequest {
+        title: "全文搜索".into(),
+        tag_id: vec![2],
+        summary: None,
+        content: "# 全文搜索\n\n正文里提到了 Postgres 的 tsvector。".into(),
+        editor_id: None,
+        status: None,
+        publish_time: None,
+    });
+    let id = client.create_post(request).await.unwrap().into_inner().id;
+
+    // 搜索正文中的词，中文按短语匹配
+    let request = Request::new(ListPostRequest {
+        page: None,
+        tag_id: vec![],
+        keyword: Some("tsvector 提到".into()),
+        is_del: None,
+        dateline_range: None,
+        match_all_tags: None,
+        status: None,
+        sort: Some(PostSort::Relevance as i32),
+        with_content: None,
+        cursor: None,
+        page_size: None,
+        with_total: None,
+    });
+    let posts = client.list_posts(request).await.unwrap().into_inner().posts;
+    let post = posts.iter().find(|post| post.id == id).unwrap();
+    let highlight = post.highlight.as_ref().unwrap();
+    assert!(highlight.snippet.contains("<mark>tsvector</mark>"));
+    assert!(highlight.snippet.contains("<mark>提到</mark>"));
+
+    // 字的顺序不同则不匹配
+    let request = Request::new(ListPostRequest {
+        page: None,
+        tag_id: vec![],
+        keyword: Some("到提".into()),
+        is_del: None,
+        dateline_range: None,
+        match_all_tags: None,
+        status: None,
+        sort: None,
+        with_content: None,
+        cursor: None,
+        page_size: None,
+        with_total: None,
+    });
+    let response = client.list_posts(request).await;
+    assert!(response.map_or(true, |r| r.into_inner().posts.iter().all(|p| p.id != id)));
+
+    let request = Request::new(ListPostRequest {
+        page: None,
+        tag_id: vec![],
+        keyword: None,
+        is_del: None,
+        dateline_range: None,
+        match_all_tags: None,
+        status: None,
+        sort: Some(PostSort::MostHits as i32),
+        with_content: None,
+        cursor: None,
+        page_size: None,
+        with_total: None,
+    });
+    let posts = client.list_posts(request).await.unwrap().into_inner().posts;
+    assert!(posts.windows(2).all(|w| w[0].hit >= w[1].hit));
+}
+
+#[tokio::test]
+async fn test_list_post_sitemap() {
+    let mut client = PostServiceClient::new(connect(util::Service::Post).unwrap());
+    let request = Request::new(ListPostSitemapRequest {
+        page: None,
+        page_size: 2,
+    });
+    let reply = client.list_post_sitemap(request).await.unwrap().into_inner();
+    dbg!(&reply);
+    assert!(reply.entries.len() <= 2);
+    assert!(reply.entries.windows(2).all(|w| w[0].id < w[1].id));
+
+    // 超出上限
+    let request = Request::new(ListPostSitemapRequest {
+        page: None,
+        page_size: 50001,
+    });
+    assert!(client.list_post_sitemap(request).await.is_err());
+}
+
+#[tokio::test]
+async fn test_dedup_views() {
+    let mut client = PostServiceClient::new(connect(util::Service::Post).unwrap());
+    let request = Request::new(CreatePostRequest {
+        title: "views".into(),
+        tag_id: vec![2],
+        summary: None,
+        content: "# views".into(),
+        editor_id: None,
+        status: None,
+        publish_time: None,
+    });
+    let id = client.create_post(request).await.unwrap().into_inner().id;
+
+    let mut hits = vec![];
+    // 同一访客重复访问只计一次，其他访客另计
+    for visitor in ["a", "a", "b"] {
+        let request = Request::new(GetPostRequest {
+            id,
+            is_del: None,
+            inc_hit: None,
+            status: None,
+            visitor: Some(visitor.into()),
+        });
+        let post = client.get_post(request).await.unwrap().into_inner().post.unwrap();
+        hits.push(post.hit);
+    }
+    assert_eq!(hits, vec![1, 1, 2]);
+}
+
+#[tokio::test]
+async fn test_record_post_view() {
+    let mut client = PostServiceClient::new(connect(util::Service::Post).unwrap());
+    // 不存在的文章不计入，避免写入时违反外键约束
+    let request = Request::new(RecordPostViewRequest {
+        post_id: i32::MAX,
+        visitor: None,
+    });
+    let err = client.record_post_view(request).await.unwrap_err();
+    assert_eq!(err.code(), tonic::Code::NotFound);
+
+    let request = Request::new(CreatePostRequest {
+        title: "record view".into(),
+        tag_id: vec![2],
+        summary: None,
+        content: "# record view".into(),
+        editor_id: None,
+        status: Some(PostStatus::Draft as i32),
+        publish_time: None,
+    });
+    let id = client.create_post(request).await.unwrap().into_inner().id;
+    let request = Request::new(RecordPostViewRequest {
+        post_id: id,
+        visitor: None,
+    });
+    let err = client.record_post_view(request).await.unwrap_err();
+    assert_eq!(err.code(), tonic::Code::NotFound);
+}
+
+#[tokio::test]
+async fn test_post_stats() {
+    let mut client = PostServiceClient::new(connect(util::Service::Post).unwrap());
+    let request = Request::new(GetDailyViewsRequest {
+        post_id: None,
+        start: "2026-10-01".into(),
+        end: "2026-10-07".into(),
+    });
+    let days = client.get_daily_views(request).await.unwrap().into_inner().days;
+    assert_eq!(days.len(), 7);
+    assert_eq!(days[0].day, "2026-10-01");
+
+    // 开始日期晚于结束日期
+    let request = Request::new(GetDailyViewsRequest {
+        post_id: Some(1),
+        start: "2026-10-07".into(),
+        end: "2026-10-01".into(),
+    });
+    assert!(client.get_daily_views(request).await.is_err());
+
+    let request = Request::new(ListTopPostsRequest {
+        start: "".into(),
+        end: "".into(),
+        limit: Some(5),
+    });
+    let posts = client.list_top_posts(request).await.unwrap().into_inner().posts;
+    assert!(posts.len() <= 5);
+    assert!(posts.windows(2).all(|p| p[0].views >= p[1].views));
+
+    let request = Request::new(CountTagPostsRequest {});
+    let tags = client.count_tag_posts(request).await.unwrap().into_inner().tags;
+    assert!(tags.windows(2).all(|t| t[0].posts >= t[1].posts));
+}
+
+#[tokio::test]
+async fn test_list_posts_cursor() {
+    let mut client = PostServiceClient::new(connect(util::Service::Post).unwrap());
+    let list = |cursor: Option<String>, sort: PostSort, page_size: i32| ListPostRequest {
+        page: None,
+        tag_id: vec![],
+        keyword: None,
+        is_del: Some(false),
+        dateline_range: None,
+        match_all_tags: None,
+        status: None,
+        sort: Some(sort as i32),
+        with_content: None,
+        cursor,
+        page_size: Some(page_size),
+        with_total: Some(true),
+    };
+    let first = client
+        .list_posts(Request::new(list(None, PostSort::Newest, 2)))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(first.posts.len(), 2);
+    assert!(first.total.unwrap() > 2);
+    let second = client
+        .list_posts(Request::new(list(first.next_cursor.clone(), PostSort::Newest, 2)))
+        .await
+        .unwrap()
+        .into_inner();
+    // 第二页紧接着第一页，没有重复，和一次取 4 篇的结果相同
+    let ids = first.posts.iter().chain(&second.posts).map(|p| p.id).collect::<Vec<i32>>();
+    let mut sorted = ids.clone();
+    sorted.sort();
+    sorted.dedup();
+    assert_eq!(sorted.len(), 4);
+    let all = client
+        .list_posts(Request::new(list(None, PostSort::Newest, 4)))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(all.posts.iter().map(|p| p.id).collect::<Vec<i32>>(), ids);
+
+    // 游标与排序方式不一致
+    let request = Request::new(list(first.next_cursor, PostSort::MostHits, 2));
+    assert!(client.list_posts(request).await.is_err());
+    let request = Request::new(list(Some("bad".into()), PostSort::Newest, 2));
+    assert!(client.list_posts(request).await.is_err());
+
+    // 超出范围的页码返回空列表
+    let mut request = list(None, PostSort::Newest, 2);
+    request.page = Some(100000);
+    let reply = client.list_posts(Request::new(request)).await.unwrap().into_inner();
+    assert!(reply.posts.is_empty());
+    assert_eq!(reply.next_cursor, None);
+}
@@ -21,6 +21,15 @@ pub struct Comment {
     pub created_at: ::core::option::Option<::prost_types::Timestamp>,
     #[prost(bool, tag = "7")]
     pub is_del: bool,
+    /// 回复的评论 id
+    #[prost(int32, optional, tag = "8")]
+    pub parent_id: ::core::option::Option<i32>,
+    /// 在回复树中的层级，顶层评论为 0
+    #[prost(int32, tag = "9")]
+    pub depth: i32,
+    /// 审核状态
+    #[prost(enumeration = "CommentStatus", tag = "10")]
+    pub status: i32,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -33,24 +42,55 @@ pub struct CreateCommentRequest {
     pub hashed_email: ::prost::alloc::string::String,
     #[prost(string, tag = "4")]
     pub content: ::prost::alloc::string::String,
+    /// 回复的评论，需属于同一篇文章
+    #[prost(int32, optional, tag = "5")]
+    pub parent_id: ::core::option::Option<i32>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct CreateCommentReply {
     #[prost(int32, tag = "1")]
     pub id: i32,
+    /// 需要审核时为 PENDING
+    #[prost(enumeration = "CommentStatus", tag = "2")]
+    pub status: i32,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
-#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetPostCommentsRequest {
     #[prost(int32, tag = "1")]
     pub post_id: i32,
+    /// 页码，从 0 开始，设置了 cursor 时忽略
+    #[prost(int32, optional, tag = "2")]
+    pub page: ::core::option::Option<i32>,
+    /// 上一页返回的 next_cursor，排序需与上一页一致
+    #[prost(string, optional, tag = "3")]
+    pub cursor: ::core::option::Option<::prost::alloc::string::String>,
+    /// 每页顶层评论数，回复跟随所属的顶层评论，默认 20，最大 100
+    #[prost(int32, optional, tag = "4")]
+    pub page_size: ::core::option::Option<i32>,
+    /// 顶层评论的排序，默认 OLDEST，回复总是从旧到新
+    #[prost(enumeration = "CommentSort", optional, tag = "5")]
+    pub sort: ::core::option::Option<i32>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetPostCommentsReply {
+    /// 按回复树深度优先排列
     #[prost(message, repeated, tag = "1")]
     pub comments: ::prost::alloc::vec::Vec<Comment>,
+    /// 文章下可见的评论总数，包括回复
+    #[prost(int64, tag = "2")]
+    pub total: i64,
+    /// 当前页码，使用 cursor 时为 0
+    #[prost(int32, tag = "3")]
+    pub page: i32,
+    /// 按顶层评论计算的总页数
+    #[prost(int32, tag = "4")]
+    pub page_total: i32,
+    /// 下一页的游标，没有下一页时为空
+    #[prost(string, optional, tag = "5")]
+    pub next_cursor: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
@@ -66,6 +106,140 @@ pub struct ToggleCommentReply {
     #[prost(bool, tag = "2")]
     pub is_del: bool,
 }
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct ListCommentsRequest {
+    /// 不指定时列出全部
+    #[prost(enumeration = "CommentStatus", optional, tag = "1")]
+    pub status: ::core::option::Option<i32>,
+    /// 页码
+    #[prost(int32, optional, tag = "2")]
+    pub page: ::core::option::Option<i32>,
+    #[prost(bool, optional, tag = "3")]
+    pub is_del: ::core::option::Option<bool>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListCommentsReply {
+    /// 当前页码
+    #[prost(int32, tag = "1")]
+    pub page: i32,
+    /// 总页数
+    #[prost(int32, tag = "2")]
+    pub page_total: i32,
+    /// 按时间从新到旧
+    #[prost(message, repeated, tag = "3")]
+    pub comments: ::prost::alloc::vec::Vec<Comment>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetCommentsStatusRequest {
+    #[prost(int32, repeated, tag = "1")]
+    pub ids: ::prost::alloc::vec::Vec<i32>,
+    #[prost(enumeration = "CommentStatus", tag = "2")]
+    pub status: i32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetCommentsStatusReply {
+    /// 实际修改了的评论
+    #[prost(int32, repeated, tag = "1")]
+    pub ids: ::prost::alloc::vec::Vec<i32>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetDailyCommentsRequest {
+    /// 不设置时统计全站
+    #[prost(int32, optional, tag = "1")]
+    pub post_id: ::core::option::Option<i32>,
+    /// YYYY-MM-DD，包含当天，为空时为 end 之前 30 天
+    #[prost(string, tag = "2")]
+    pub start: ::prost::alloc::string::String,
+    /// YYYY-MM-DD，包含当天，为空时为今天
+    #[prost(string, tag = "3")]
+    pub end: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DailyComments {
+    /// YYYY-MM-DD
+    #[prost(string, tag = "1")]
+    pub day: ::prost::alloc::string::String,
+    /// 未删除的评论数，包括待审核的
+    #[prost(int64, tag = "2")]
+    pub comments: i64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetDailyCommentsReply {
+    /// 按日期从早到晚，没有评论的日期为 0
+    #[prost(message, repeated, tag = "1")]
+    pub days: ::prost::alloc::vec::Vec<DailyComments>,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum CommentStatus {
+    /// 已通过，公开显示
+    Approved = 0,
+    /// 待审核
+    Pending = 1,
+    /// 垃圾评论
+    Spam = 2,
+    /// 已拒绝
+    Rejected = 3,
+}
+impl CommentStatus {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            CommentStatus::Approved => "APPROVED",
+            CommentStatus::Pending => "PENDING",
+            CommentStatus::Spam => "SPAM",
+            CommentStatus::Rejected => "REJECTED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "APPROVED" => Some(Self::Approved),
+            "PENDING" => Some(Self::Pending),
+            "SPAM" => Some(Self::Spam),
+            "REJECTED" => Some(Self::Rejected),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum CommentSort {
+    /// 按发表时间从旧到新
+    Oldest = 0,
+    /// 按发表时间从新到旧
+    Newest = 1,
+}
+impl CommentSort {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            CommentSort::Oldest => "OLDEST",
+            CommentSort::Newest => "NEWEST",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "OLDEST" => Some(Self::Oldest),
+            "NEWEST" => Some(Self::Newest),
+            _ => None,
+        }
+    }
+}
 /// Generated client implementations.
 pub mod comment_service_client {
     #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
@@ -229,6 +403,84 @@ pub mod comment_service_client {
                 .insert(GrpcMethod::new("comment.CommentService", "ToggleComment"));
             self.inner.unary(req, path, codec).await
         }
+        /// (后台) 按审核状态列出所有文章的评论
+        pub async fn list_comments(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListCommentsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListCommentsReply>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/comment.CommentService/ListComments",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("comment.CommentService", "ListComments"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// (后台) 批量修改评论的审核状态
+        pub async fn set_comments_status(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SetCommentsStatusRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::SetCommentsStatusReply>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/comment.CommentService/SetCommentsStatus",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("comment.CommentService", "SetCommentsStatus"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// (后台) 每天的评论数
+        pub async fn get_daily_comments(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetDailyCommentsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetDailyCommentsReply>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/comment.CommentService/GetDailyComments",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("comment.CommentService", "GetDailyComments"));
+            self.inner.unary(req, path, codec).await
+        }
     }
 }
 /// Generated server implementations.
@@ -262,6 +514,30 @@ pub mod comment_service_server {
             tonic::Response<super::ToggleCommentReply>,
             tonic::Status,
         >;
+        /// (后台) 按审核状态列出所有文章的评论
+        async fn list_comments(
+            &self,
+            request: tonic::Request<super::ListCommentsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListCommentsReply>,
+            tonic::Status,
+        >;
+        /// (后台) 批量修改评论的审核状态
+        async fn set_comments_status(
+            &self,
+            request: tonic::Request<super::SetCommentsStatusRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::SetCommentsStatusReply>,
+            tonic::Status,
+        >;
+        /// (后台) 每天的评论数
+        async fn get_daily_comments(
+            &self,
+            request: tonic::Request<super::GetDailyCommentsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetDailyCommentsReply>,
+            tonic::Status,
+        >;
     }
     #[derive(Debug)]
     pub struct CommentServiceServer<T: CommentService> {
@@ -475,6 +751,143 @@ pub mod comment_service_server {
                     };
                     Box::pin(fut)
                 }
+                "/comment.CommentService/ListComments" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListCommentsSvc<T: CommentService>(pub Arc<T>);
+                    impl<
+                        T: CommentService,
+                    > tonic::server::UnaryService<super::ListCommentsRequest>
+                    for ListCommentsSvc<T> {
+                        type Response = super::ListCommentsReply;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListCommentsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CommentService>::list_comments(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ListCommentsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/comment.CommentService/SetCommentsStatus" => {
+                    #[allow(non_camel_case_types)]
+                    struct SetCommentsStatusSvc<T: CommentService>(pub Arc<T>);
+                    impl<
+                        T: CommentService,
+                    > tonic::server::UnaryService<super::SetCommentsStatusRequest>
+                    for SetCommentsStatusSvc<T> {
+                        type Response = super::SetCommentsStatusReply;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SetCommentsStatusRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CommentService>::set_comments_status(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = SetCommentsStatusSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/comment.CommentService/GetDailyComments" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetDailyCommentsSvc<T: CommentService>(pub Arc<T>);
+                    impl<
+                        T: CommentService,
+                    > tonic::server::UnaryService<super::GetDailyCommentsRequest>
+                    for GetDailyCommentsSvc<T> {
+                        type Response = super::GetDailyCommentsReply;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetDailyCommentsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CommentService>::get_daily_comments(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetDailyCommentsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         Ok(
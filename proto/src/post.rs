@@ -13,12 +13,6 @@ pub struct Post {
     /// 摘要
     #[prost(string, tag = "4")]
     pub summary: ::prost::alloc::string::String,
-    /// markdown 文件的路径
-    #[prost(string, tag = "5")]
-    pub md_path: ::prost::alloc::string::String,
-    /// 根据 markdown 生成的 html 文件路径
-    #[prost(string, tag = "6")]
-    pub html_path: ::prost::alloc::string::String,
     /// 点击数
     #[prost(int32, tag = "7")]
     pub hit: i32,
@@ -34,6 +28,28 @@ pub struct Post {
     /// 最后更新时间
     #[prost(message, optional, tag = "11")]
     pub update_time: ::core::option::Option<::prost_types::Timestamp>,
+    /// markdown 原文，GetPost 或 ListPosts 指定 with_content 时返回
+    #[prost(string, optional, tag = "12")]
+    pub md_content: ::core::option::Option<::prost::alloc::string::String>,
+    /// 根据 markdown 生成的 html，同上
+    #[prost(string, optional, tag = "13")]
+    pub html_content: ::core::option::Option<::prost::alloc::string::String>,
+    /// 状态，定时发布的文章到达发布时间后为 PUBLISHED
+    #[prost(enumeration = "PostStatus", tag = "14")]
+    pub status: i32,
+    /// 关键字高亮，仅按关键字搜索时返回
+    #[prost(message, optional, tag = "15")]
+    pub highlight: ::core::option::Option<SearchHighlight>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SearchHighlight {
+    /// 关键字用 <mark> 包裹的标题，已转义 HTML
+    #[prost(string, tag = "1")]
+    pub title: ::prost::alloc::string::String,
+    /// 正文中包含关键字的片段，同上
+    #[prost(string, tag = "2")]
+    pub snippet: ::prost::alloc::string::String,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -42,12 +58,21 @@ pub struct CreatePostRequest {
     pub title: ::prost::alloc::string::String,
     #[prost(int32, repeated, tag = "2")]
     pub tag_id: ::prost::alloc::vec::Vec<i32>,
-    /// 根据 md_path 读取 markdown 文件，然后转换为 html
-    #[prost(string, tag = "3")]
-    pub md_path: ::prost::alloc::string::String,
     /// 如果没有提供摘要，则自动从内容中截取
     #[prost(string, optional, tag = "4")]
     pub summary: ::core::option::Option<::prost::alloc::string::String>,
+    /// markdown 原文，保存时同时转换为 html
+    #[prost(string, tag = "5")]
+    pub content: ::prost::alloc::string::String,
+    /// 创建文章的管理员 id，记录在修订中
+    #[prost(int32, optional, tag = "6")]
+    pub editor_id: ::core::option::Option<i32>,
+    /// 默认为 PUBLISHED
+    #[prost(enumeration = "PostStatus", optional, tag = "7")]
+    pub status: ::core::option::Option<i32>,
+    /// 默认为当前时间，SCHEDULED 时必须提供
+    #[prost(message, optional, tag = "8")]
+    pub publish_time: ::core::option::Option<::prost_types::Timestamp>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
@@ -64,10 +89,20 @@ pub struct EditPostRequest {
     pub title: ::prost::alloc::string::String,
     #[prost(int32, repeated, tag = "3")]
     pub tag_id: ::prost::alloc::vec::Vec<i32>,
-    #[prost(string, tag = "4")]
-    pub md_path: ::prost::alloc::string::String,
     #[prost(string, optional, tag = "5")]
     pub summary: ::core::option::Option<::prost::alloc::string::String>,
+    /// markdown 原文
+    #[prost(string, tag = "6")]
+    pub content: ::prost::alloc::string::String,
+    /// 修改文章的管理员 id，记录在修订中
+    #[prost(int32, optional, tag = "7")]
+    pub editor_id: ::core::option::Option<i32>,
+    /// 不提供则保持不变
+    #[prost(enumeration = "PostStatus", optional, tag = "8")]
+    pub status: ::core::option::Option<i32>,
+    /// 不提供则保持不变
+    #[prost(message, optional, tag = "9")]
+    pub publish_time: ::core::option::Option<::prost_types::Timestamp>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
@@ -80,13 +115,13 @@ pub struct EditPostReply {
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ListPostRequest {
-    /// 页码
+    /// 页码，从 0 开始，设置了 cursor 时忽略
     #[prost(int32, optional, tag = "1")]
     pub page: ::core::option::Option<i32>,
-    /// 标签
-    #[prost(int32, optional, tag = "2")]
-    pub tag_id: ::core::option::Option<i32>,
-    /// 关键字
+    /// 标签，默认文章包含其中任意一个即可
+    #[prost(int32, repeated, tag = "2")]
+    pub tag_id: ::prost::alloc::vec::Vec<i32>,
+    /// 关键字，全文搜索标题、摘要和正文
     #[prost(string, optional, tag = "3")]
     pub keyword: ::core::option::Option<::prost::alloc::string::String>,
     /// 是否删除
@@ -95,18 +130,46 @@ pub struct ListPostRequest {
     /// 时间区间
     #[prost(message, optional, tag = "5")]
     pub dateline_range: ::core::option::Option<DatelineRange>,
+    /// 为 true 时文章需包含全部标签
+    #[prost(bool, optional, tag = "6")]
+    pub match_all_tags: ::core::option::Option<bool>,
+    /// PUBLISHED 表示当前可见的文章，SCHEDULED 表示尚未到发布时间的文章
+    #[prost(enumeration = "PostStatus", optional, tag = "7")]
+    pub status: ::core::option::Option<i32>,
+    /// 有关键字时默认 RELEVANCE，否则默认 NEWEST
+    #[prost(enumeration = "PostSort", optional, tag = "8")]
+    pub sort: ::core::option::Option<i32>,
+    /// 为 true 时附带文章内容，用于订阅源输出全文
+    #[prost(bool, optional, tag = "9")]
+    pub with_content: ::core::option::Option<bool>,
+    /// 上一页返回的 next_cursor，查询条件和排序需与上一页一致
+    #[prost(string, optional, tag = "10")]
+    pub cursor: ::core::option::Option<::prost::alloc::string::String>,
+    /// 每页数量，默认 10，最大 100
+    #[prost(int32, optional, tag = "11")]
+    pub page_size: ::core::option::Option<i32>,
+    /// 为 true 时返回 total 和 page_total，需要额外的 COUNT 查询
+    #[prost(bool, optional, tag = "12")]
+    pub with_total: ::core::option::Option<bool>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ListPostReply {
-    /// 当前页码
+    /// 当前页码，使用 cursor 时为 0
     #[prost(int32, tag = "1")]
     pub page: i32,
-    /// 总页数
+    /// 总页数，仅 with_total 为 true 时返回
     #[prost(int32, tag = "2")]
     pub page_total: i32,
+    /// 没有文章时为空
     #[prost(message, repeated, tag = "3")]
     pub posts: ::prost::alloc::vec::Vec<Post>,
+    /// 下一页的游标，没有下一页时为空
+    #[prost(string, optional, tag = "4")]
+    pub next_cursor: ::core::option::Option<::prost::alloc::string::String>,
+    /// 文章总数，仅 with_total 为 true 时返回
+    #[prost(int64, optional, tag = "5")]
+    pub total: ::core::option::Option<i64>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
@@ -131,7 +194,7 @@ pub struct TogglePostReply {
     pub is_del: bool,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
-#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetPostRequest {
     #[prost(int32, tag = "1")]
     pub id: i32,
@@ -140,6 +203,12 @@ pub struct GetPostRequest {
     /// 是否增加点击量
     #[prost(bool, optional, tag = "3")]
     pub inc_hit: ::core::option::Option<bool>,
+    /// 同 ListPostRequest.status
+    #[prost(enumeration = "PostStatus", optional, tag = "4")]
+    pub status: ::core::option::Option<i32>,
+    /// 访客标识，设置时同一访客在去重窗口内只计一次点击
+    #[prost(string, optional, tag = "5")]
+    pub visitor: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -147,6 +216,328 @@ pub struct GetPostReply {
     #[prost(message, optional, tag = "1")]
     pub post: ::core::option::Option<Post>,
 }
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RecordPostViewRequest {
+    #[prost(int32, tag = "1")]
+    pub post_id: i32,
+    /// 同 GetPostRequest.visitor
+    #[prost(string, optional, tag = "2")]
+    pub visitor: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct RecordPostViewReply {
+    /// 是否计入，去重窗口内的重复浏览为 false
+    #[prost(bool, tag = "1")]
+    pub counted: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PostRevision {
+    #[prost(int32, tag = "1")]
+    pub id: i32,
+    #[prost(int32, tag = "2")]
+    pub post_id: i32,
+    #[prost(string, tag = "3")]
+    pub title: ::prost::alloc::string::String,
+    #[prost(int32, repeated, tag = "4")]
+    pub tag_id: ::prost::alloc::vec::Vec<i32>,
+    #[prost(string, tag = "5")]
+    pub summary: ::prost::alloc::string::String,
+    /// markdown 原文，列表中不返回
+    #[prost(string, optional, tag = "6")]
+    pub md_content: ::core::option::Option<::prost::alloc::string::String>,
+    /// 修改者
+    #[prost(int32, optional, tag = "7")]
+    pub editor_id: ::core::option::Option<i32>,
+    /// 修订时间
+    #[prost(message, optional, tag = "8")]
+    pub created_at: ::core::option::Option<::prost_types::Timestamp>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct ListPostRevisionsRequest {
+    #[prost(int32, tag = "1")]
+    pub post_id: i32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListPostRevisionsReply {
+    /// 按时间从新到旧
+    #[prost(message, repeated, tag = "1")]
+    pub revisions: ::prost::alloc::vec::Vec<PostRevision>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct DiffPostRevisionsRequest {
+    /// 旧修订
+    #[prost(int32, tag = "1")]
+    pub from_id: i32,
+    /// 新修订
+    #[prost(int32, tag = "2")]
+    pub to_id: i32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DiffLine {
+    #[prost(enumeration = "diff_line::Op", tag = "1")]
+    pub op: i32,
+    #[prost(string, tag = "2")]
+    pub content: ::prost::alloc::string::String,
+}
+/// Nested message and enum types in `DiffLine`.
+pub mod diff_line {
+    #[derive(
+        Clone,
+        Copy,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash,
+        PartialOrd,
+        Ord,
+        ::prost::Enumeration
+    )]
+    #[repr(i32)]
+    pub enum Op {
+        Equal = 0,
+        Insert = 1,
+        Delete = 2,
+    }
+    impl Op {
+        /// String value of the enum field names used in the ProtoBuf definition.
+        ///
+        /// The values are not transformed in any way and thus are considered stable
+        /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+        pub fn as_str_name(&self) -> &'static str {
+            match self {
+                Op::Equal => "EQUAL",
+                Op::Insert => "INSERT",
+                Op::Delete => "DELETE",
+            }
+        }
+        /// Creates an enum from field names used in the ProtoBuf definition.
+        pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+            match value {
+                "EQUAL" => Some(Self::Equal),
+                "INSERT" => Some(Self::Insert),
+                "DELETE" => Some(Self::Delete),
+                _ => None,
+            }
+        }
+    }
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DiffPostRevisionsReply {
+    #[prost(message, optional, tag = "1")]
+    pub from: ::core::option::Option<PostRevision>,
+    #[prost(message, optional, tag = "2")]
+    pub to: ::core::option::Option<PostRevision>,
+    /// markdown 原文的逐行差异
+    #[prost(message, repeated, tag = "3")]
+    pub lines: ::prost::alloc::vec::Vec<DiffLine>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct RestorePostRevisionRequest {
+    #[prost(int32, tag = "1")]
+    pub post_id: i32,
+    #[prost(int32, tag = "2")]
+    pub revision_id: i32,
+    /// 执行恢复的管理员 id
+    #[prost(int32, optional, tag = "3")]
+    pub editor_id: ::core::option::Option<i32>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct RestorePostRevisionReply {
+    #[prost(int32, tag = "1")]
+    pub id: i32,
+    #[prost(bool, tag = "2")]
+    pub ok: bool,
+    /// 恢复后新产生的修订
+    #[prost(int32, tag = "3")]
+    pub revision_id: i32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct ListPostSitemapRequest {
+    /// 页码，从 0 开始
+    #[prost(int32, optional, tag = "1")]
+    pub page: ::core::option::Option<i32>,
+    /// 每页数量，最大 50000
+    #[prost(int32, tag = "2")]
+    pub page_size: i32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct SitemapEntry {
+    #[prost(int32, tag = "1")]
+    pub id: i32,
+    /// 最后更新时间，没有修改过时为发布时间
+    #[prost(message, optional, tag = "2")]
+    pub lastmod: ::core::option::Option<::prost_types::Timestamp>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListPostSitemapReply {
+    /// 已发布文章总数
+    #[prost(int64, tag = "1")]
+    pub total: i64,
+    /// 按 id 从小到大
+    #[prost(message, repeated, tag = "2")]
+    pub entries: ::prost::alloc::vec::Vec<SitemapEntry>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetDailyViewsRequest {
+    /// 不设置时统计全站
+    #[prost(int32, optional, tag = "1")]
+    pub post_id: ::core::option::Option<i32>,
+    /// YYYY-MM-DD，包含当天，为空时为 end 之前 30 天
+    #[prost(string, tag = "2")]
+    pub start: ::prost::alloc::string::String,
+    /// YYYY-MM-DD，包含当天，为空时为今天
+    #[prost(string, tag = "3")]
+    pub end: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DailyViews {
+    /// YYYY-MM-DD
+    #[prost(string, tag = "1")]
+    pub day: ::prost::alloc::string::String,
+    #[prost(int64, tag = "2")]
+    pub views: i64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetDailyViewsReply {
+    /// 按日期从早到晚，没有浏览的日期为 0
+    #[prost(message, repeated, tag = "1")]
+    pub days: ::prost::alloc::vec::Vec<DailyViews>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListTopPostsRequest {
+    /// 同 GetDailyViewsRequest.start
+    #[prost(string, tag = "1")]
+    pub start: ::prost::alloc::string::String,
+    /// 同 GetDailyViewsRequest.end
+    #[prost(string, tag = "2")]
+    pub end: ::prost::alloc::string::String,
+    /// 默认 10，最大 100
+    #[prost(int32, optional, tag = "3")]
+    pub limit: ::core::option::Option<i32>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TopPost {
+    #[prost(int32, tag = "1")]
+    pub id: i32,
+    #[prost(string, tag = "2")]
+    pub title: ::prost::alloc::string::String,
+    #[prost(int64, tag = "3")]
+    pub views: i64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListTopPostsReply {
+    /// 按浏览量从多到少
+    #[prost(message, repeated, tag = "1")]
+    pub posts: ::prost::alloc::vec::Vec<TopPost>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct CountTagPostsRequest {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TagPostCount {
+    #[prost(int32, tag = "1")]
+    pub tag_id: i32,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+    /// 已发布且未删除的文章数
+    #[prost(int64, tag = "3")]
+    pub posts: i64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CountTagPostsReply {
+    /// 未删除的标签，按文章数从多到少
+    #[prost(message, repeated, tag = "1")]
+    pub tags: ::prost::alloc::vec::Vec<TagPostCount>,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum PostStatus {
+    /// 已发布，publish_time 在未来时同 SCHEDULED
+    Published = 0,
+    /// 草稿
+    Draft = 1,
+    /// 定时发布，到达 publish_time 后自动可见
+    Scheduled = 2,
+    /// 已归档
+    Archived = 3,
+}
+impl PostStatus {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            PostStatus::Published => "PUBLISHED",
+            PostStatus::Draft => "DRAFT",
+            PostStatus::Scheduled => "SCHEDULED",
+            PostStatus::Archived => "ARCHIVED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "PUBLISHED" => Some(Self::Published),
+            "DRAFT" => Some(Self::Draft),
+            "SCHEDULED" => Some(Self::Scheduled),
+            "ARCHIVED" => Some(Self::Archived),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum PostSort {
+    /// 按发布时间从新到旧
+    Newest = 0,
+    /// 按关键字相关度，没有关键字时同 NEWEST
+    Relevance = 1,
+    /// 按点击数从多到少
+    MostHits = 2,
+}
+impl PostSort {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            PostSort::Newest => "NEWEST",
+            PostSort::Relevance => "RELEVANCE",
+            PostSort::MostHits => "MOST_HITS",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "NEWEST" => Some(Self::Newest),
+            "RELEVANCE" => Some(Self::Relevance),
+            "MOST_HITS" => Some(Self::MostHits),
+            _ => None,
+        }
+    }
+}
 /// Generated client implementations.
 pub mod post_service_client {
     #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
@@ -349,80 +740,352 @@ pub mod post_service_client {
             req.extensions_mut().insert(GrpcMethod::new("post.PostService", "GetPost"));
             self.inner.unary(req, path, codec).await
         }
-    }
-}
-/// Generated server implementations.
-pub mod post_service_server {
-    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
-    use tonic::codegen::*;
-    /// Generated trait containing gRPC methods that should be implemented for use with PostServiceServer.
-    #[async_trait]
-    pub trait PostService: Send + Sync + 'static {
-        /// 创建文章
-        async fn create_post(
-            &self,
-            request: tonic::Request<super::CreatePostRequest>,
-        ) -> std::result::Result<tonic::Response<super::CreatePostReply>, tonic::Status>;
-        /// 修改文章
-        async fn edit_post(
-            &self,
-            request: tonic::Request<super::EditPostRequest>,
-        ) -> std::result::Result<tonic::Response<super::EditPostReply>, tonic::Status>;
-        /// 根据信息搜索文章
-        async fn list_posts(
-            &self,
-            request: tonic::Request<super::ListPostRequest>,
-        ) -> std::result::Result<tonic::Response<super::ListPostReply>, tonic::Status>;
-        /// 删除/恢复文章
-        async fn toggle_post(
-            &self,
-            request: tonic::Request<super::TogglePostRequest>,
-        ) -> std::result::Result<tonic::Response<super::TogglePostReply>, tonic::Status>;
-        /// 获取文章
-        async fn get_post(
-            &self,
-            request: tonic::Request<super::GetPostRequest>,
-        ) -> std::result::Result<tonic::Response<super::GetPostReply>, tonic::Status>;
-    }
-    #[derive(Debug)]
-    pub struct PostServiceServer<T: PostService> {
-        inner: Arc<T>,
-        accept_compression_encodings: EnabledCompressionEncodings,
-        send_compression_encodings: EnabledCompressionEncodings,
-        max_decoding_message_size: Option<usize>,
-        max_encoding_message_size: Option<usize>,
-    }
-    impl<T: PostService> PostServiceServer<T> {
-        pub fn new(inner: T) -> Self {
-            Self::from_arc(Arc::new(inner))
-        }
-        pub fn from_arc(inner: Arc<T>) -> Self {
-            Self {
-                inner,
-                accept_compression_encodings: Default::default(),
-                send_compression_encodings: Default::default(),
-                max_decoding_message_size: None,
-                max_encoding_message_size: None,
-            }
-        }
-        pub fn with_interceptor<F>(
-            inner: T,
-            interceptor: F,
-        ) -> InterceptedService<Self, F>
-        where
-            F: tonic::service::Interceptor,
-        {
-            InterceptedService::new(Self::new(inner), interceptor)
+        /// 文章修订记录列表
+        pub async fn list_post_revisions(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListPostRevisionsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListPostRevisionsReply>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/post.PostService/ListPostRevisions",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("post.PostService", "ListPostRevisions"));
+            self.inner.unary(req, path, codec).await
         }
-        /// Enable decompressing requests with the given encoding.
-        #[must_use]
-        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
-            self.accept_compression_encodings.enable(encoding);
-            self
+        /// 比较两个修订
+        pub async fn diff_post_revisions(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DiffPostRevisionsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::DiffPostRevisionsReply>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/post.PostService/DiffPostRevisions",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("post.PostService", "DiffPostRevisions"));
+            self.inner.unary(req, path, codec).await
         }
-        /// Compress responses with the given encoding, if the client supports it.
-        #[must_use]
-        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+        /// 恢复到某个修订
+        pub async fn restore_post_revision(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RestorePostRevisionRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RestorePostRevisionReply>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/post.PostService/RestorePostRevision",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("post.PostService", "RestorePostRevision"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 站点地图用的已发布文章 id 及最后修改时间
+        pub async fn list_post_sitemap(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListPostSitemapRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListPostSitemapReply>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/post.PostService/ListPostSitemap",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("post.PostService", "ListPostSitemap"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// (后台) 每天的浏览量，单篇文章或全站
+        pub async fn get_daily_views(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetDailyViewsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetDailyViewsReply>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/post.PostService/GetDailyViews",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("post.PostService", "GetDailyViews"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// (后台) 一段时间内浏览量最多的文章
+        pub async fn list_top_posts(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListTopPostsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListTopPostsReply>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/post.PostService/ListTopPosts",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("post.PostService", "ListTopPosts"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// (后台) 每个标签下的文章数
+        pub async fn count_tag_posts(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CountTagPostsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CountTagPostsReply>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/post.PostService/CountTagPosts",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("post.PostService", "CountTagPosts"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 只记录一次浏览，网关直接返回缓存的文章时使用
+        pub async fn record_post_view(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RecordPostViewRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RecordPostViewReply>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/post.PostService/RecordPostView",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("post.PostService", "RecordPostView"));
+            self.inner.unary(req, path, codec).await
+        }
+    }
+}
+/// Generated server implementations.
+pub mod post_service_server {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with PostServiceServer.
+    #[async_trait]
+    pub trait PostService: Send + Sync + 'static {
+        /// 创建文章
+        async fn create_post(
+            &self,
+            request: tonic::Request<super::CreatePostRequest>,
+        ) -> std::result::Result<tonic::Response<super::CreatePostReply>, tonic::Status>;
+        /// 修改文章
+        async fn edit_post(
+            &self,
+            request: tonic::Request<super::EditPostRequest>,
+        ) -> std::result::Result<tonic::Response<super::EditPostReply>, tonic::Status>;
+        /// 根据信息搜索文章
+        async fn list_posts(
+            &self,
+            request: tonic::Request<super::ListPostRequest>,
+        ) -> std::result::Result<tonic::Response<super::ListPostReply>, tonic::Status>;
+        /// 删除/恢复文章
+        async fn toggle_post(
+            &self,
+            request: tonic::Request<super::TogglePostRequest>,
+        ) -> std::result::Result<tonic::Response<super::TogglePostReply>, tonic::Status>;
+        /// 获取文章
+        async fn get_post(
+            &self,
+            request: tonic::Request<super::GetPostRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetPostReply>, tonic::Status>;
+        /// 文章修订记录列表
+        async fn list_post_revisions(
+            &self,
+            request: tonic::Request<super::ListPostRevisionsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListPostRevisionsReply>,
+            tonic::Status,
+        >;
+        /// 比较两个修订
+        async fn diff_post_revisions(
+            &self,
+            request: tonic::Request<super::DiffPostRevisionsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::DiffPostRevisionsReply>,
+            tonic::Status,
+        >;
+        /// 恢复到某个修订
+        async fn restore_post_revision(
+            &self,
+            request: tonic::Request<super::RestorePostRevisionRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RestorePostRevisionReply>,
+            tonic::Status,
+        >;
+        /// 站点地图用的已发布文章 id 及最后修改时间
+        async fn list_post_sitemap(
+            &self,
+            request: tonic::Request<super::ListPostSitemapRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListPostSitemapReply>,
+            tonic::Status,
+        >;
+        /// (后台) 每天的浏览量，单篇文章或全站
+        async fn get_daily_views(
+            &self,
+            request: tonic::Request<super::GetDailyViewsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetDailyViewsReply>,
+            tonic::Status,
+        >;
+        /// (后台) 一段时间内浏览量最多的文章
+        async fn list_top_posts(
+            &self,
+            request: tonic::Request<super::ListTopPostsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListTopPostsReply>,
+            tonic::Status,
+        >;
+        /// (后台) 每个标签下的文章数
+        async fn count_tag_posts(
+            &self,
+            request: tonic::Request<super::CountTagPostsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CountTagPostsReply>,
+            tonic::Status,
+        >;
+        /// 只记录一次浏览，网关直接返回缓存的文章时使用
+        async fn record_post_view(
+            &self,
+            request: tonic::Request<super::RecordPostViewRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RecordPostViewReply>,
+            tonic::Status,
+        >;
+    }
+    #[derive(Debug)]
+    pub struct PostServiceServer<T: PostService> {
+        inner: Arc<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+        max_decoding_message_size: Option<usize>,
+        max_encoding_message_size: Option<usize>,
+    }
+    impl<T: PostService> PostServiceServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+                max_decoding_message_size: None,
+                max_encoding_message_size: None,
+            }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        /// Enable decompressing requests with the given encoding.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.accept_compression_encodings.enable(encoding);
+            self
+        }
+        /// Compress responses with the given encoding, if the client supports it.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
             self.send_compression_encodings.enable(encoding);
             self
         }
@@ -685,6 +1348,369 @@ pub mod post_service_server {
                     };
                     Box::pin(fut)
                 }
+                "/post.PostService/ListPostRevisions" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListPostRevisionsSvc<T: PostService>(pub Arc<T>);
+                    impl<
+                        T: PostService,
+                    > tonic::server::UnaryService<super::ListPostRevisionsRequest>
+                    for ListPostRevisionsSvc<T> {
+                        type Response = super::ListPostRevisionsReply;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListPostRevisionsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as PostService>::list_post_revisions(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ListPostRevisionsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/post.PostService/DiffPostRevisions" => {
+                    #[allow(non_camel_case_types)]
+                    struct DiffPostRevisionsSvc<T: PostService>(pub Arc<T>);
+                    impl<
+                        T: PostService,
+                    > tonic::server::UnaryService<super::DiffPostRevisionsRequest>
+                    for DiffPostRevisionsSvc<T> {
+                        type Response = super::DiffPostRevisionsReply;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DiffPostRevisionsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as PostService>::diff_post_revisions(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = DiffPostRevisionsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/post.PostService/RestorePostRevision" => {
+                    #[allow(non_camel_case_types)]
+                    struct RestorePostRevisionSvc<T: PostService>(pub Arc<T>);
+                    impl<
+                        T: PostService,
+                    > tonic::server::UnaryService<super::RestorePostRevisionRequest>
+                    for RestorePostRevisionSvc<T> {
+                        type Response = super::RestorePostRevisionReply;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RestorePostRevisionRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as PostService>::restore_post_revision(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = RestorePostRevisionSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/post.PostService/ListPostSitemap" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListPostSitemapSvc<T: PostService>(pub Arc<T>);
+                    impl<
+                        T: PostService,
+                    > tonic::server::UnaryService<super::ListPostSitemapRequest>
+                    for ListPostSitemapSvc<T> {
+                        type Response = super::ListPostSitemapReply;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListPostSitemapRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as PostService>::list_post_sitemap(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ListPostSitemapSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/post.PostService/GetDailyViews" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetDailyViewsSvc<T: PostService>(pub Arc<T>);
+                    impl<
+                        T: PostService,
+                    > tonic::server::UnaryService<super::GetDailyViewsRequest>
+                    for GetDailyViewsSvc<T> {
+                        type Response = super::GetDailyViewsReply;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetDailyViewsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as PostService>::get_daily_views(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetDailyViewsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/post.PostService/ListTopPosts" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListTopPostsSvc<T: PostService>(pub Arc<T>);
+                    impl<
+                        T: PostService,
+                    > tonic::server::UnaryService<super::ListTopPostsRequest>
+                    for ListTopPostsSvc<T> {
+                        type Response = super::ListTopPostsReply;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListTopPostsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as PostService>::list_top_posts(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ListTopPostsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/post.PostService/CountTagPosts" => {
+                    #[allow(non_camel_case_types)]
+                    struct CountTagPostsSvc<T: PostService>(pub Arc<T>);
+                    impl<
+                        T: PostService,
+                    > tonic::server::UnaryService<super::CountTagPostsRequest>
+                    for CountTagPostsSvc<T> {
+                        type Response = super::CountTagPostsReply;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CountTagPostsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as PostService>::count_tag_posts(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = CountTagPostsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/post.PostService/RecordPostView" => {
+                    #[allow(non_camel_case_types)]
+                    struct RecordPostViewSvc<T: PostService>(pub Arc<T>);
+                    impl<
+                        T: PostService,
+                    > tonic::server::UnaryService<super::RecordPostViewRequest>
+                    for RecordPostViewSvc<T> {
+                        type Response = super::RecordPostViewReply;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RecordPostViewRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as PostService>::record_post_view(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = RecordPostViewSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         Ok(
@@ -1,275 +0,0 @@
---
--- PostgreSQL database dump
---
-
--- Dumped from database version 16.2 (Postgres.app)
--- Dumped by pg_dump version 16.2 (Postgres.app)
-
-SET statement_timeout = 0;
-SET lock_timeout = 0;
-SET idle_in_transaction_session_timeout = 0;
-SET client_encoding = 'UTF8';
-SET standard_conforming_strings = on;
-SELECT pg_catalog.set_config('search_path', '', false);
-SET check_function_bodies = false;
-SET xmloption = content;
-SET client_min_messages = warning;
-SET row_security = off;
-
-SET default_tablespace = '';
-
-SET default_table_access_method = heap;
-
---
--- Name: admin; Type: TABLE; Schema: public; Owner: postgres
---
-
-CREATE TABLE public.admin (
-    id integer NOT NULL,
-    email character varying(255) NOT NULL,
-    password character varying(255) NOT NULL,
-    is_del boolean DEFAULT false NOT NULL
-);
-
-
-ALTER TABLE public.admin OWNER TO postgres;
-
---
--- Name: admin_id_seq; Type: SEQUENCE; Schema: public; Owner: postgres
---
-
-CREATE SEQUENCE public.admin_id_seq
-    AS integer
-    START WITH 1
-    INCREMENT BY 1
-    NO MINVALUE
-    NO MAXVALUE
-    CACHE 1;
-
-
-ALTER SEQUENCE public.admin_id_seq OWNER TO postgres;
-
---
--- Name: admin_id_seq; Type: SEQUENCE OWNED BY; Schema: public; Owner: postgres
---
-
-ALTER SEQUENCE public.admin_id_seq OWNED BY public.admin.id;
-
-
---
--- Name: comment; Type: TABLE; Schema: public; Owner: postgres
---
-
-CREATE TABLE public.comment (
-    id integer NOT NULL,
-    post_id integer NOT NULL,
-    name character varying(255) NOT NULL,
-    hashed_email character varying(255),
-    content character varying(200),
-    created_at timestamp with time zone DEFAULT CURRENT_TIMESTAMP NOT NULL,
-    is_del boolean DEFAULT false NOT NULL
-);
-
-
-ALTER TABLE public.comment OWNER TO postgres;
-
---
--- Name: comment_id_seq; Type: SEQUENCE; Schema: public; Owner: postgres
---
-
-CREATE SEQUENCE public.comment_id_seq
-    AS integer
-    START WITH 1
-    INCREMENT BY 1
-    NO MINVALUE
-    NO MAXVALUE
-    CACHE 1;
-
-
-ALTER SEQUENCE public.comment_id_seq OWNER TO postgres;
-
---
--- Name: comment_id_seq; Type: SEQUENCE OWNED BY; Schema: public; Owner: postgres
---
-
-ALTER SEQUENCE public.comment_id_seq OWNED BY public.comment.id;
-
-
---
--- Name: post; Type: TABLE; Schema: public; Owner: postgres
---
-
-CREATE TABLE public.post (
-    id integer NOT NULL,
-    title character varying(255) NOT NULL,
-    summary character varying(255) NOT NULL,
-    md_path character varying(255) NOT NULL,
-    html_path character varying(255) NOT NULL,
-    hit integer DEFAULT 0 NOT NULL,
-    words_len integer DEFAULT 0,
-    is_del boolean DEFAULT false NOT NULL,
-    publish_time timestamp with time zone DEFAULT CURRENT_TIMESTAMP NOT NULL,
-    update_time timestamp with time zone,
-    tag_id bytea
-);
-
-
-ALTER TABLE public.post OWNER TO postgres;
-
---
--- Name: post_id_seq; Type: SEQUENCE; Schema: public; Owner: postgres
---
-
-CREATE SEQUENCE public.post_id_seq
-    AS integer
-    START WITH 1
-    INCREMENT BY 1
-    NO MINVALUE
-    NO MAXVALUE
-    CACHE 1;
-
-
-ALTER SEQUENCE public.post_id_seq OWNER TO postgres;
-
---
--- Name: post_id_seq; Type: SEQUENCE OWNED BY; Schema: public; Owner: postgres
---
-
-ALTER SEQUENCE public.post_id_seq OWNED BY public.post.id;
-
-
---
--- Name: tag; Type: TABLE; Schema: public; Owner: postgres
---
-
-CREATE TABLE public.tag (
-    id integer NOT NULL,
-    name character varying(100) NOT NULL,
-    is_del boolean NOT NULL
-);
-
-
-ALTER TABLE public.tag OWNER TO postgres;
-
---
--- Name: table_name_id_seq; Type: SEQUENCE; Schema: public; Owner: postgres
---
-
-CREATE SEQUENCE public.table_name_id_seq
-    AS integer
-    START WITH 1
-    INCREMENT BY 1
-    NO MINVALUE
-    NO MAXVALUE
-    CACHE 1;
-
-
-ALTER SEQUENCE public.table_name_id_seq OWNER TO postgres;
-
---
--- Name: table_name_id_seq; Type: SEQUENCE OWNED BY; Schema: public; Owner: postgres
---
-
-ALTER SEQUENCE public.table_name_id_seq OWNED BY public.tag.id;
-
-
---
--- Name: admin id; Type: DEFAULT; Schema: public; Owner: postgres
---
-
-ALTER TABLE ONLY public.admin ALTER COLUMN id SET DEFAULT nextval('public.admin_id_seq'::regclass);
-
-
---
--- Name: comment id; Type: DEFAULT; Schema: public; Owner: postgres
---
-
-ALTER TABLE ONLY public.comment ALTER COLUMN id SET DEFAULT nextval('public.comment_id_seq'::regclass);
-
-
---
--- Name: post id; Type: DEFAULT; Schema: public; Owner: postgres
---
-
-ALTER TABLE ONLY public.post ALTER COLUMN id SET DEFAULT nextval('public.post_id_seq'::regclass);
-
-
---
--- Name: tag id; Type: DEFAULT; Schema: public; Owner: postgres
---
-
-ALTER TABLE ONLY public.tag ALTER COLUMN id SET DEFAULT nextval('public.table_name_id_seq'::regclass);
-
-
---
--- Name: admin_id_seq; Type: SEQUENCE SET; Schema: public; Owner: postgres
---
-
-SELECT pg_catalog.setval('public.admin_id_seq', 1, false);
-
-
---
--- Name: comment_id_seq; Type: SEQUENCE SET; Schema: public; Owner: postgres
---
-
-SELECT pg_catalog.setval('public.comment_id_seq', 1, false);
-
-
---
--- Name: post_id_seq; Type: SEQUENCE SET; Schema: public; Owner: postgres
---
-
-SELECT pg_catalog.setval('public.post_id_seq', 3, true);
-
-
---
--- Name: table_name_id_seq; Type: SEQUENCE SET; Schema: public; Owner: postgres
---
-
-SELECT pg_catalog.setval('public.table_name_id_seq', 5, true);
-
-
---
--- Name: admin admin_pkey; Type: CONSTRAINT; Schema: public; Owner: postgres
---
-
-ALTER TABLE ONLY public.admin
-    ADD CONSTRAINT admin_pkey PRIMARY KEY (id);
-
-
---
--- Name: comment comment_pkey; Type: CONSTRAINT; Schema: public; Owner: postgres
---
-
-ALTER TABLE ONLY public.comment
-    ADD CONSTRAINT comment_pkey PRIMARY KEY (id);
-
-
---
--- Name: post post_pkey; Type: CONSTRAINT; Schema: public; Owner: postgres
---
-
-ALTER TABLE ONLY public.post
-    ADD CONSTRAINT post_pkey PRIMARY KEY (id);
-
-
---
--- Name: tag table_name_pkey; Type: CONSTRAINT; Schema: public; Owner: postgres
---
-
-ALTER TABLE ONLY public.tag
-    ADD CONSTRAINT table_name_pkey PRIMARY KEY (id);
-
-
---
--- Name: comment comment_post_id_fk; Type: FK CONSTRAINT; Schema: public; Owner: postgres
---
-
-ALTER TABLE ONLY public.comment
-    ADD CONSTRAINT comment_post_id_fk FOREIGN KEY (post_id) REFERENCES public.post(id);
-
-
---
--- PostgreSQL database dump complete
---
-
@@ -2,12 +2,15 @@ use anyhow::{anyhow, Result};
 use entity::entity::tag::{self, Column};
 use sea_orm::{prelude::*, Set};
 use sea_orm::{DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter};
+use tracing::instrument;
 
+#[instrument(skip_all)]
 pub async fn select_tag_exists_by_id(db: &DatabaseConnection, id: i32) -> Result<i32> {
     let rows = entity::Tag::find_by_id(id).count(db).await?;
     Ok(rows as i32)
 }
 
+#[instrument(skip_all)]
 pub async fn select_tag_exists_by_name(db: &DatabaseConnection, name: &str) -> Result<i32> {
     let rows = entity::Tag::find()
         .filter(Column::Name.eq(name))
@@ -16,6 +19,7 @@ pub async fn select_tag_exists_by_name(db: &DatabaseConnection, name: &str) -> R
     Ok(rows as i32)
 }
 
+#[instrument(skip_all)]
 pub async fn insert_new_tag(db: &DatabaseConnection, name: &str) -> Result<i32> {
     let new_tag = tag::ActiveModel {
         name: Set(name.to_owned()),
@@ -26,6 +30,7 @@ pub async fn insert_new_tag(db: &DatabaseConnection, name: &str) -> Result<i32>
     Ok(res.last_insert_id)
 }
 
+#[instrument(skip_all)]
 pub async fn update_tag(db: &DatabaseConnection, id: i32, name: &str) -> Result<u64> {
     let res = entity::Tag::update_many()
         .filter(Column::Id.eq(id))
@@ -35,6 +40,7 @@ pub async fn update_tag(db: &DatabaseConnection, id: i32, name: &str) -> Result<
     Ok(res.rows_affected)
 }
 
+#[instrument(skip_all)]
 pub async fn select_tags(
     db: &DatabaseConnection,
     name: &Option<String>,
@@ -51,6 +57,7 @@ pub async fn select_tags(
     Ok(tags)
 }
 
+#[instrument(skip_all)]
 pub async fn update_tag_del(db: &DatabaseConnection, id: i32) -> Result<bool> {
     let res = entity::Tag::update_many()
         .filter(Column::Id.eq(id))
@@ -64,6 +71,7 @@ pub async fn update_tag_del(db: &DatabaseConnection, id: i32) -> Result<bool> {
     }
 }
 
+#[instrument(skip_all)]
 pub async fn select_tag_info(
     db: &DatabaseConnection,
     id: i32,
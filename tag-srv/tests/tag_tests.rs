@@ -3,12 +3,11 @@ use proto::{
     EditTagRequest, GetTagInfoRequest, ListTagsRequest, TagExistsRequest, ToggleTagRequest,
 };
 use tonic::Request;
-use util::get_service_url;
+use util::interceptor::connect;
 
 #[tokio::test]
 async fn test_create_tag() {
-    let client_url = get_service_url(util::Service::Tag).unwrap();
-    let mut client = TagServiceClient::connect(client_url).await.unwrap();
+    let mut client = TagServiceClient::new(connect(util::Service::Tag).unwrap());
     let request = Request::new(CreateTagRequest {
         name: "tag1".to_string(),
     });
@@ -19,8 +18,7 @@ async fn test_create_tag() {
 
 #[tokio::test]
 async fn test_edit_tag() {
-    let client_url = get_service_url(util::Service::Tag).unwrap();
-    let mut client = TagServiceClient::connect(client_url).await.unwrap();
+    let mut client = TagServiceClient::new(connect(util::Service::Tag).unwrap());
     let request = Request::new(EditTagRequest {
         id: 234243,
         name: "tag1_changed".to_string(),
@@ -47,8 +45,7 @@ async fn test_edit_tag() {
 
 #[tokio::test]
 async fn test_list_tags() {
-    let client_url = get_service_url(util::Service::Tag).unwrap();
-    let mut client = TagServiceClient::connect(client_url).await.unwrap();
+    let mut client = TagServiceClient::new(connect(util::Service::Tag).unwrap());
     let request = Request::new(ListTagsRequest {
         name: Some("tag".to_string()),
         is_del: None,
@@ -80,8 +77,7 @@ async fn test_list_tags() {
 
 #[tokio::test]
 async fn test_toggle_tag() {
-    let client_url = get_service_url(util::Service::Tag).unwrap();
-    let mut client = TagServiceClient::connect(client_url).await.unwrap();
+    let mut client = TagServiceClient::new(connect(util::Service::Tag).unwrap());
     let req = Request::new(ToggleTagRequest { id: 2 });
     let reply = client.toggle_tag(req).await.unwrap();
     dbg!(reply.into_inner());
@@ -89,8 +85,7 @@ async fn test_toggle_tag() {
 
 #[tokio::test]
 async fn test_tag_exists() {
-    let client_url = get_service_url(util::Service::Tag).unwrap();
-    let mut client = TagServiceClient::connect(client_url).await.unwrap();
+    let mut client = TagServiceClient::new(connect(util::Service::Tag).unwrap());
     let request = Request::new(TagExistsRequest {
         condition: Some(Condition::Name("tag2".to_string())),
     });
@@ -118,8 +113,7 @@ async fn test_tag_exists() {
 
 #[tokio::test]
 async fn test_get_tag_info() {
-    let client_url = get_service_url(util::Service::Tag).unwrap();
-    let mut client = TagServiceClient::connect(client_url).await.unwrap();
+    let mut client = TagServiceClient::new(connect(util::Service::Tag).unwrap());
 
     let request = Request::new(GetTagInfoRequest {
         id: 2,
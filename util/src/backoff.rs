@@ -0,0 +1,135 @@
+// 连接其他服务失败后按指数退避重连，退避期间的调用直接失败，不再反复尝试连接
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tonic::transport::Uri;
+use tower_service::Service;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+#[derive(Default)]
+struct BackoffState {
+    failures: u32,             // 连续失败的次数
+    retry_at: Option<Instant>, // 在此之前不再尝试连接
+}
+
+/// 包装 tonic 使用的 connector，连接成功后清零失败次数
+#[derive(Clone)]
+pub struct BackoffConnector<C> {
+    inner: C,
+    initial: Duration, // 第一次失败后的等待时间，之后每次翻倍
+    max: Duration,
+    state: Arc<Mutex<BackoffState>>,
+}
+
+impl<C> BackoffConnector<C> {
+    pub fn new(inner: C, initial: Duration, max: Duration) -> Self {
+        Self {
+            inner,
+            initial,
+            max,
+            state: Arc::new(Mutex::new(BackoffState::default())),
+        }
+    }
+}
+
+impl BackoffState {
+    /// delay 第 failures 次连续失败后的等待时间
+    fn delay(failures: u32, initial: Duration, max: Duration) -> Duration {
+        let factor = 1u32 << failures.saturating_sub(1).min(16);
+        initial.saturating_mul(factor).min(max)
+    }
+
+    fn failed(&mut self, initial: Duration, max: Duration, now: Instant) -> Duration {
+        self.failures = self.failures.saturating_add(1);
+        let delay = Self::delay(self.failures, initial, max);
+        self.retry_at = Some(now + delay);
+        delay
+    }
+
+    fn connected(&mut self) {
+        self.failures = 0;
+        self.retry_at = None;
+    }
+}
+
+impl<C> Service<Uri> for BackoffConnector<C>
+where
+    C: Service<Uri>,
+    C::Future: Send + 'static,
+    C::Error: Into<BoxError>,
+{
+    type Response = C::Response;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<C::Response, BoxError>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, uri: Uri) -> Self::Future {
+        let retry_at = self.state.lock().unwrap().retry_at;
+        if let Some(retry_at) = retry_at.filter(|&at| Instant::now() < at) {
+            let remaining = retry_at - Instant::now();
+            return Box::pin(async move {
+                Err(format!(
+                    "connection backing off, retry in {}ms",
+                    remaining.as_millis()
+                )
+                .into())
+            });
+        }
+
+        let connecting = self.inner.call(uri.clone());
+        let (state, initial, max) = (self.state.clone(), self.initial, self.max);
+        Box::pin(async move {
+            match connecting.await {
+                Ok(io) => {
+                    let mut state = state.lock().unwrap();
+                    if state.failures > 0 {
+                        tracing::info!("Reconnected to {}", uri);
+                    }
+                    state.connected();
+                    Ok(io)
+                }
+                Err(err) => {
+                    let err = err.into();
+                    let delay = state.lock().unwrap().failed(initial, max, Instant::now());
+                    tracing::warn!(
+                        "Connect to {} failed: {}, retry in {}ms",
+                        uri,
+                        err,
+                        delay.as_millis()
+                    );
+                    Err(err)
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backoff::BackoffState;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_backoff_delay() {
+        let (initial, max) = (Duration::from_millis(500), Duration::from_secs(5));
+        let mut state = BackoffState::default();
+        let now = Instant::now();
+        let delays = (0..6)
+            .map(|_| state.failed(initial, max, now).as_millis())
+            .collect::<Vec<u128>>();
+        assert_eq!(delays, vec![500, 1000, 2000, 4000, 5000, 5000]);
+        assert_eq!(state.retry_at, Some(now + max));
+
+        state.connected();
+        assert_eq!(state.failures, 0);
+        assert!(state.retry_at.is_none());
+        assert_eq!(BackoffState::delay(u32::MAX, initial, max), max);
+    }
+}
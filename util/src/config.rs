@@ -0,0 +1,1129 @@
+// 各服务的配置，按 默认值 < 原来的环境变量 < TOML 配置文件 < 环境变量 < 命令行参数 的顺序覆盖，启动时校验
+
+use crate::Service;
+use anyhow::{anyhow, Result};
+use dotenv::dotenv;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::fs;
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+use toml::{Table, Value};
+use tonic::metadata::{Ascii, MetadataValue};
+use tonic::transport::{Certificate, ClientTlsConfig, Endpoint, Identity, ServerTlsConfig};
+
+/// 没有通过 --config 或 LILY_CONFIG 指定时，读取当前目录下的这个文件（可以不存在）
+const DEFAULT_CONFIG_FILE: &str = "config.toml";
+/// 通用的环境变量覆盖，LILY__SERVICES__POST__BIND 对应 services.post.bind
+const ENV_PREFIX: &str = "LILY__";
+
+/// 默认配置，features 中列出的是各服务支持的全部功能开关
+const DEFAULTS: &str = r#"
+[database]
+url = ""
+max_connections = 10
+min_connections = 1
+connect_timeout = 8
+idle_timeout = 600
+auto_migrate = false
+health_check_interval = 10
+
+[client]
+connect_timeout = 3
+request_timeout = 10
+keepalive = 30
+backoff_initial = 1
+backoff_max = 30
+
+[tracing]
+exporter = "none"
+endpoint = "http://localhost:4317"
+file = "traces.jsonl"
+sample_ratio = 1.0
+
+[auth]
+token_secret = ""
+token_expire = 86400
+service_token = ""
+
+[site]
+url = "http://localhost:8081"
+title = "Lily Blog"
+description = ""
+robots_allow = []
+robots_disallow = ["/api/"]
+
+[http]
+trusted_proxies = []
+cache_ttl = 30
+cache_max_bytes = 16777216
+
+[http.rate_limits]
+new_comment = "5/60"
+search_posts = "30/60"
+login = "10/60"
+
+[views]
+dedup_window = 1800
+flush_interval = 10
+visitor_salt = ""
+
+[spam]
+max_links = 2
+bayes_threshold = 0.9
+
+[services.tag]
+bind = "[::1]:12345"
+metrics = "[::1]:9101"
+
+[services.post]
+bind = "[::1]:12346"
+metrics = "[::1]:9102"
+
+[services.comment]
+bind = "[::1]:12347"
+metrics = "[::1]:9103"
+
+[services.comment.features]
+require_approval = false
+
+[services.admin]
+bind = "[::1]:12348"
+metrics = "[::1]:9104"
+
+[services.blog_api]
+bind = "[::1]:8081"
+
+[services.blog_api.features]
+feed_full_content = false
+"#;
+
+const USAGE: &str = "usage: [--config <file>] [--bind <addr>] [--database-url <url>] \
+                     [--feature <name>=<true|false>] [--set <key>=<value>]";
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub database: DatabaseConfig,
+    pub client: ClientConfig,
+    pub tracing: TracingConfig,
+    pub auth: AuthConfig,
+    pub site: SiteConfig,
+    pub http: HttpConfig,
+    pub views: ViewsConfig,
+    pub spam: SpamConfig,
+    pub services: Services,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DatabaseConfig {
+    pub url: String,
+    pub max_connections: u32,
+    pub min_connections: u32,
+    pub connect_timeout: u64,       // 秒
+    pub idle_timeout: u64,          // 秒
+    pub auto_migrate: bool,         // 服务启动时执行尚未应用的数据库迁移
+    pub health_check_interval: u64, // 秒，定时 ping 数据库更新健康检查的状态
+}
+
+/// 连接其他服务的 gRPC client
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ClientConfig {
+    pub connect_timeout: u64, // 秒
+    pub request_timeout: u64, // 秒，单次调用的超时
+    pub keepalive: u64,       // 秒，TCP 和 HTTP/2 keepalive 的间隔
+    pub backoff_initial: u64, // 秒，连接失败后第一次重连前的等待，之后每次翻倍
+    pub backoff_max: u64,     // 秒，重连等待的上限
+}
+
+/// OpenTelemetry 链路追踪
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TracingConfig {
+    pub exporter: TraceExporter,
+    pub endpoint: String,  // OTLP gRPC 地址
+    pub file: PathBuf,     // exporter 为 file 时追加写入的文件
+    pub sample_ratio: f64, // 采样比例，上游已采样的调用总是记录
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TraceExporter {
+    None,
+    Otlp,
+    Stdout,
+    File,
+}
+
+/// 管理员 token 和服务之间调用的鉴权
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AuthConfig {
+    pub token_secret: String,  // 签发管理员 token 的密钥，blog-api 必须设置
+    pub token_expire: u64,     // 秒，管理员 token 的有效期
+    pub service_token: String, // 服务之间调用时携带的 token，各服务必须设置
+}
+
+/// blog-api 面向外部的站点信息，用于订阅源、站点地图和 robots.txt
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SiteConfig {
+    pub url: String, // 前端站点地址
+    pub title: String,
+    pub description: String,
+    pub robots_allow: Vec<String>,
+    pub robots_disallow: Vec<String>,
+}
+
+/// blog-api 的限流和响应缓存
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HttpConfig {
+    pub rate_limits: BTreeMap<String, RateLimit>, // 路由名 -> 限制，没有配置的路由不限流
+    pub trusted_proxies: Vec<IpAddr>,             // 只采用来自这些反向代理的 X-Forwarded-For
+    pub cache_ttl: u64,                           // 秒，为 0 时不缓存，只做 ETag 协商
+    pub cache_max_bytes: usize,                   // 缓存的响应体总大小上限
+}
+
+/// 每 period 最多 capacity 次请求，配置中写作 请求数/秒数，如 5/60
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct RateLimit {
+    pub capacity: u32,
+    pub period: Duration,
+}
+
+/// post-srv 的浏览量统计
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ViewsConfig {
+    pub dedup_window: u64,   // 秒，同一访客在窗口内只计一次
+    pub flush_interval: u64, // 秒，浏览量写入数据库的间隔
+    pub visitor_salt: String, // blog-api 计算访客标识的密钥，为空时每次启动随机生成
+}
+
+/// comment-srv 的垃圾评论检查
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpamConfig {
+    pub max_links: usize,     // 评论中最多的链接数
+    pub bayes_threshold: f64, // 判断为垃圾评论的概率阈值
+    #[serde(default)]
+    pub blocklist_file: Option<PathBuf>, // 屏蔽词文件，每行一个，以 re: 开头的行为正则表达式
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Services {
+    pub tag: ServiceConfig,
+    pub post: ServiceConfig,
+    pub comment: ServiceConfig,
+    pub admin: ServiceConfig,
+    pub blog_api: ServiceConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ServiceConfig {
+    pub bind: String, // 监听地址
+    #[serde(default)]
+    pub url: Option<String>, // 其他服务连接时使用的地址，默认由 bind 得到
+    #[serde(default)]
+    pub metrics: Option<String>, // gRPC 服务的 /metrics 监听地址，为空时不启动；blog-api 在 bind 上提供
+    #[serde(default)]
+    pub tls: TlsConfig,
+    #[serde(default)]
+    pub features: BTreeMap<String, bool>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TlsConfig {
+    pub cert: Option<PathBuf>, // 服务端证书，和 key 一起设置时启用 TLS
+    pub key: Option<PathBuf>,
+    pub ca: Option<PathBuf>,    // 连接这个服务时用来校验证书的 CA
+    pub domain: Option<String>, // 连接时校验的域名，默认为 url 中的主机名
+}
+
+impl Service {
+    /// 配置文件中的名字
+    pub fn key(&self) -> &'static str {
+        match self {
+            Service::Tag => "tag",
+            Service::Post => "post",
+            Service::Comment => "comment",
+            Service::Admin => "admin",
+            Service::BlogApi => "blog_api",
+        }
+    }
+
+    /// 环境变量的前缀，如 POST_SRV_BIND
+    fn env_prefix(&self) -> &'static str {
+        match self {
+            Service::Tag => "TAG_SRV",
+            Service::Post => "POST_SRV",
+            Service::Comment => "COMMENT_SRV",
+            Service::Admin => "ADMIN_SRV",
+            Service::BlogApi => "BLOG_API",
+        }
+    }
+
+    /// 这个服务会连接的其他服务
+    fn upstreams(&self) -> &'static [Service] {
+        match self {
+            Service::BlogApi => &ALL_SERVICES[..4],
+            _ => &[],
+        }
+    }
+}
+
+const ALL_SERVICES: [Service; 5] = [
+    Service::Tag,
+    Service::Post,
+    Service::Comment,
+    Service::Admin,
+    Service::BlogApi,
+];
+
+/// init 服务启动时调用，读取命令行参数加载配置并校验
+pub fn init(srv: Service) -> Result<&'static Config> {
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    let config = Config::load(Some(srv), &args)?;
+    Ok(CONFIG.get_or_init(|| config))
+}
+
+/// get 返回 init 加载的配置；没有调用过 init 时（如测试中）只从配置文件和环境变量加载
+pub fn get() -> Result<&'static Config> {
+    if let Some(config) = CONFIG.get() {
+        return Ok(config);
+    }
+    let config = Config::load(None, &[])?;
+    Ok(CONFIG.get_or_init(|| config))
+}
+
+impl Config {
+    /// load 加载并校验配置，srv 为 None 时不检查只有服务启动时才需要的项
+    pub fn load(srv: Option<Service>, args: &[String]) -> Result<Self> {
+        dotenv().ok();
+        let cli = parse_args(srv, args)?;
+        let path = cli
+            .config
+            .clone()
+            .or_else(|| env::var("LILY_CONFIG").ok().map(PathBuf::from));
+        let file = match path {
+            Some(path) => Some(read_file(&path)?),
+            None if Path::new(DEFAULT_CONFIG_FILE).exists() => {
+                Some(read_file(Path::new(DEFAULT_CONFIG_FILE))?)
+            }
+            None => None,
+        };
+        // 跳过不是 UTF-8 的变量，它们不会是配置项
+        let vars = env::vars_os()
+            .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)))
+            .collect::<HashMap<String, String>>();
+        let legacy = legacy_env(&vars)?;
+        let env = env_overrides(&vars)?;
+        Self::from_layers(srv, legacy, file, env, cli.sets)
+    }
+
+    fn from_layers(
+        srv: Option<Service>,
+        legacy: Vec<(String, Value)>,
+        file: Option<Table>,
+        env: Vec<(String, Value)>,
+        cli: Vec<(String, Value)>,
+    ) -> Result<Self> {
+        let defaults = DEFAULTS.parse::<Table>()?;
+        let mut table = defaults.clone();
+        for (key, value) in legacy {
+            set_path(&mut table, &key, value)?;
+        }
+        if let Some(file) = file {
+            merge(&mut table, file);
+        }
+        for (key, value) in env.into_iter().chain(cli) {
+            set_path(&mut table, &key, value)?;
+        }
+        let config = Config::deserialize(Value::Table(table))
+            .map_err(|err| anyhow!("invalid config: {}", err.message()))?;
+        config.validate(srv, &defaults)?;
+        Ok(config)
+    }
+
+    pub fn service(&self, srv: Service) -> &ServiceConfig {
+        match srv {
+            Service::Tag => &self.services.tag,
+            Service::Post => &self.services.post,
+            Service::Comment => &self.services.comment,
+            Service::Admin => &self.services.admin,
+            Service::BlogApi => &self.services.blog_api,
+        }
+    }
+
+    /// feature 服务的功能开关，已在校验时确认 name 存在
+    pub fn feature(&self, srv: Service, name: &str) -> bool {
+        self.service(srv)
+            .features
+            .get(name)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// validate 一次列出所有错误
+    fn validate(&self, srv: Option<Service>, defaults: &Table) -> Result<()> {
+        let mut errors = vec![];
+        let database = &self.database;
+        if database.url.is_empty() {
+            if srv.is_some_and(|srv| srv != Service::BlogApi) {
+                errors.push("database.url is required (DATABASE_URL)".to_owned());
+            }
+        } else if !database.url.starts_with("postgres://")
+            && !database.url.starts_with("postgresql://")
+        {
+            errors.push("database.url must be a postgres:// url".to_owned());
+        }
+        if database.max_connections == 0 {
+            errors.push("database.max_connections must be greater than 0".to_owned());
+        }
+        if database.health_check_interval == 0 {
+            errors.push("database.health_check_interval must be greater than 0".to_owned());
+        }
+        if database.min_connections > database.max_connections {
+            errors.push("database.min_connections must not exceed max_connections".to_owned());
+        }
+        let client = &self.client;
+        for (key, value) in [
+            ("connect_timeout", client.connect_timeout),
+            ("request_timeout", client.request_timeout),
+            ("keepalive", client.keepalive),
+            ("backoff_initial", client.backoff_initial),
+        ] {
+            if value == 0 {
+                errors.push(format!("client.{} must be greater than 0", key));
+            }
+        }
+        if client.backoff_initial > client.backoff_max {
+            errors.push("client.backoff_initial must not exceed backoff_max".to_owned());
+        }
+        let tracing = &self.tracing;
+        if !(0.0..=1.0).contains(&tracing.sample_ratio) {
+            errors.push("tracing.sample_ratio must be between 0 and 1".to_owned());
+        }
+        let auth = &self.auth;
+        if auth.token_secret.is_empty() && srv == Some(Service::BlogApi) {
+            errors.push("auth.token_secret is required (TOKEN_SECRET)".to_owned());
+        }
+        if auth.token_expire == 0 {
+            errors.push("auth.token_expire must be greater than 0".to_owned());
+        }
+        if auth.service_token.is_empty() {
+            if srv.is_some() {
+                errors.push("auth.service_token is required (SERVICE_TOKEN)".to_owned());
+            }
+        } else if auth.service_token.parse::<MetadataValue<Ascii>>().is_err() {
+            errors.push("auth.service_token must be printable ASCII".to_owned());
+        }
+        if !self.site.url.starts_with("http://") && !self.site.url.starts_with("https://") {
+            errors.push("site.url must start with http:// or https://".to_owned());
+        }
+        if self.views.flush_interval == 0 {
+            errors.push("views.flush_interval must be greater than 0".to_owned());
+        }
+        let spam = &self.spam;
+        if !(0.0..=1.0).contains(&spam.bayes_threshold) {
+            errors.push("spam.bayes_threshold must be between 0 and 1".to_owned());
+        }
+        if let Some(path) = &spam.blocklist_file {
+            if srv == Some(Service::Comment) && !path.is_file() {
+                errors.push(format!(
+                    "spam.blocklist_file: file not found: {}",
+                    path.display()
+                ));
+            }
+        }
+        if tracing.exporter == TraceExporter::Otlp {
+            if let Err(err) = Endpoint::from_shared(tracing.endpoint.clone()) {
+                errors.push(format!(
+                    "tracing.endpoint: invalid url \"{}\": {}",
+                    tracing.endpoint, err
+                ));
+            }
+        }
+
+        for service in ALL_SERVICES {
+            let name = format!("services.{}", service.key());
+            let config = self.service(service);
+            let known = defaults["services"][service.key()]
+                .get("features")
+                .and_then(Value::as_table);
+            for feature in config.features.keys() {
+                if !known.is_some_and(|known| known.contains_key(feature)) {
+                    errors.push(format!("{}.features.{}: unknown feature", name, feature));
+                }
+            }
+            if config.bind.parse::<SocketAddr>().is_err() {
+                errors.push(format!(
+                    "{}.bind: invalid socket address \"{}\"",
+                    name, config.bind
+                ));
+            }
+            if let Some(addr) = config.metrics_bind() {
+                if addr.parse::<SocketAddr>().is_err() {
+                    errors.push(format!(
+                        "{}.metrics: invalid socket address \"{}\"",
+                        name, addr
+                    ));
+                }
+            }
+            let tls = &config.tls;
+            if tls.cert.is_some() != tls.key.is_some() {
+                errors.push(format!("{}.tls: cert and key must be set together", name));
+            }
+            let url = config.url();
+            if let Err(err) = Endpoint::from_shared(url.clone()) {
+                errors.push(format!("{}.url: invalid url \"{}\": {}", name, url, err));
+            }
+            let https = url.starts_with("https://");
+            if !https && !url.starts_with("http://") {
+                errors.push(format!("{}.url must start with http:// or https://", name));
+            }
+
+            // 只检查这个服务自己会读取的文件和会连接的服务，没有指定服务时按会连接全部后端服务检查
+            let mut files = vec![];
+            if srv == Some(service) {
+                files.extend([("cert", &tls.cert), ("key", &tls.key)]);
+            }
+            let connects = match srv {
+                Some(srv) => srv.upstreams().contains(&service),
+                None => service != Service::BlogApi,
+            };
+            if connects {
+                if https && tls.ca.is_none() {
+                    errors.push(format!("{}.tls.ca is required for an https url", name));
+                }
+                if !https && tls.ca.is_some() {
+                    errors.push(format!("{}.tls.ca is set but url is not https", name));
+                }
+                files.push(("ca", &tls.ca));
+                if config.url.is_none() && is_unspecified(&config.bind) {
+                    errors.push(format!(
+                        "{}.url is required when bind is an unspecified address",
+                        name
+                    ));
+                }
+            }
+            for (field, path) in files {
+                if let Some(path) = path.as_ref().filter(|path| !path.is_file()) {
+                    errors.push(format!(
+                        "{}.tls.{}: file not found: {}",
+                        name,
+                        field,
+                        path.display()
+                    ));
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!("invalid config:\n  {}", errors.join("\n  ")))
+        }
+    }
+}
+
+impl TryFrom<String> for RateLimit {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        let limit = text.split_once('/').and_then(|(capacity, seconds)| {
+            let capacity = capacity.trim().parse().ok().filter(|c| *c > 0)?;
+            let seconds = seconds.trim().parse().ok().filter(|s| *s > 0)?;
+            Some(Self {
+                capacity,
+                period: Duration::from_secs(seconds),
+            })
+        });
+        limit.ok_or_else(|| {
+            format!(
+                "invalid rate limit \"{}\", expected <requests>/<seconds>",
+                text
+            )
+        })
+    }
+}
+
+impl AuthConfig {
+    pub fn token_expire(&self) -> Duration {
+        Duration::from_secs(self.token_expire)
+    }
+}
+
+impl SiteConfig {
+    /// base_url 不以 / 结尾的站点地址
+    pub fn base_url(&self) -> &str {
+        self.url.trim_end_matches('/')
+    }
+}
+
+impl HttpConfig {
+    pub fn cache_ttl(&self) -> Duration {
+        Duration::from_secs(self.cache_ttl)
+    }
+}
+
+impl ViewsConfig {
+    pub fn dedup_window(&self) -> Duration {
+        Duration::from_secs(self.dedup_window)
+    }
+
+    pub fn flush_interval(&self) -> Duration {
+        Duration::from_secs(self.flush_interval)
+    }
+}
+
+impl DatabaseConfig {
+    pub fn connect_timeout(&self) -> Duration {
+        Duration::from_secs(self.connect_timeout)
+    }
+
+    pub fn idle_timeout(&self) -> Duration {
+        Duration::from_secs(self.idle_timeout)
+    }
+
+    pub fn health_check_interval(&self) -> Duration {
+        Duration::from_secs(self.health_check_interval)
+    }
+}
+
+impl ClientConfig {
+    pub fn connect_timeout(&self) -> Duration {
+        Duration::from_secs(self.connect_timeout)
+    }
+
+    pub fn request_timeout(&self) -> Duration {
+        Duration::from_secs(self.request_timeout)
+    }
+
+    pub fn keepalive(&self) -> Duration {
+        Duration::from_secs(self.keepalive)
+    }
+
+    pub fn backoff_initial(&self) -> Duration {
+        Duration::from_secs(self.backoff_initial)
+    }
+
+    pub fn backoff_max(&self) -> Duration {
+        Duration::from_secs(self.backoff_max)
+    }
+}
+
+impl ServiceConfig {
+    /// metrics_bind 配置为空字符串时关闭
+    pub fn metrics_bind(&self) -> Option<&str> {
+        self.metrics.as_deref().filter(|addr| !addr.is_empty())
+    }
+
+    /// url 连接这个服务的地址，没有配置时由 bind 得到，启用 TLS 时为 https
+    pub fn url(&self) -> String {
+        match &self.url {
+            Some(url) => url.clone(),
+            None if self.tls.cert.is_some() => format!("https://{}", self.bind),
+            None => format!("http://{}", self.bind),
+        }
+    }
+
+    /// server_tls 配置了证书时的服务端 TLS
+    pub fn server_tls(&self) -> Result<Option<ServerTlsConfig>> {
+        match (&self.tls.cert, &self.tls.key) {
+            (Some(cert), Some(key)) => {
+                let identity = Identity::from_pem(fs::read(cert)?, fs::read(key)?);
+                Ok(Some(ServerTlsConfig::new().identity(identity)))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// client_tls 连接 https 地址时的客户端 TLS
+    pub fn client_tls(&self) -> Result<Option<ClientTlsConfig>> {
+        let Some(ca) = &self.tls.ca else {
+            return Ok(None);
+        };
+        let mut tls = ClientTlsConfig::new().ca_certificate(Certificate::from_pem(fs::read(ca)?));
+        if let Some(domain) = &self.tls.domain {
+            tls = tls.domain_name(domain);
+        }
+        Ok(Some(tls))
+    }
+}
+
+fn is_unspecified(bind: &str) -> bool {
+    bind.parse::<SocketAddr>()
+        .is_ok_and(|addr| addr.ip().is_unspecified())
+}
+
+fn read_file(path: &Path) -> Result<Table> {
+    let text = fs::read_to_string(path)
+        .map_err(|err| anyhow!("config file {}: {}", path.display(), err))?;
+    text.parse::<Table>()
+        .map_err(|err| anyhow!("config file {}: {}", path.display(), err))
+}
+
+/// merge 把 from 合并进 into，表逐层合并，其他值直接覆盖
+fn merge(into: &mut Table, from: Table) {
+    for (key, value) in from {
+        match (into.get_mut(&key), value) {
+            (Some(Value::Table(into)), Value::Table(from)) => merge(into, from),
+            (_, value) => {
+                into.insert(key, value);
+            }
+        }
+    }
+}
+
+/// set_path 按 a.b.c 形式的路径设置值，中间的表不存在时创建
+fn set_path(table: &mut Table, path: &str, value: Value) -> Result<()> {
+    let mut keys = path.split('.').collect::<Vec<&str>>();
+    let last = keys.pop().filter(|key| !key.is_empty());
+    let Some(last) = last else {
+        return Err(anyhow!("invalid config key \"{}\"", path));
+    };
+    let mut table = table;
+    for key in keys {
+        let entry = table
+            .entry(key)
+            .or_insert_with(|| Value::Table(Table::new()));
+        table = match entry {
+            Value::Table(table) => table,
+            _ => {
+                return Err(anyhow!(
+                    "invalid config key \"{}\": {} is not a table",
+                    path,
+                    key
+                ))
+            }
+        };
+    }
+    table.insert(last.to_owned(), value);
+    Ok(())
+}
+
+/// parse_value 环境变量和命令行中的值：true/false、整数、小数，其余作为字符串
+fn parse_value(text: &str) -> Value {
+    if let Ok(value) = text.parse::<bool>() {
+        Value::Boolean(value)
+    } else if let Ok(value) = text.parse::<i64>() {
+        Value::Integer(value)
+    } else if let Ok(value) = text.parse::<f64>() {
+        Value::Float(value)
+    } else {
+        Value::String(text.to_owned())
+    }
+}
+
+/// env_flag 同原来的环境变量开关，true 或 1 为 true
+fn env_flag(value: &str) -> Value {
+    Value::Boolean(value == "true" || value == "1")
+}
+
+/// env_integer 整数类型的环境变量，格式错误时指出变量名
+fn env_integer(name: &str, value: &str) -> Result<Value> {
+    value
+        .trim()
+        .parse::<i64>()
+        .map(Value::Integer)
+        .map_err(|_| anyhow!("{}: invalid integer \"{}\"", name, value))
+}
+
+/// env_list 逗号分隔的环境变量
+fn env_list(value: &str) -> Value {
+    Value::Array(
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| Value::String(s.to_owned()))
+            .collect(),
+    )
+}
+
+/// legacy_env 兼容原来的 *_PORT、DATABASE_URL 等变量，它们通常来自 .env，
+/// 只作为默认值使用，配置文件中设置了的项以配置文件为准
+fn legacy_env(vars: &HashMap<String, String>) -> Result<Vec<(String, Value)>> {
+    let var = |name: &str| vars.get(name).cloned();
+    let mut res = vec![];
+    if let Some(url) = var("DATABASE_URL") {
+        res.push(("database.url".to_owned(), Value::String(url)));
+    }
+    if let Some(value) = var("AUTO_MIGRATE") {
+        res.push(("database.auto_migrate".to_owned(), env_flag(&value)));
+    }
+    for service in ALL_SERVICES {
+        if let Some(port) = var(&format!("{}_PORT", service.env_prefix())) {
+            res.push((
+                format!("services.{}.bind", service.key()),
+                Value::String(format!("[::1]:{}", port)),
+            ));
+        }
+    }
+    if let Some(value) = var("COMMENT_REQUIRE_APPROVAL") {
+        res.push((
+            "services.comment.features.require_approval".to_owned(),
+            env_flag(&value),
+        ));
+    }
+    if let Some(value) = var("FEED_FULL_CONTENT") {
+        res.push((
+            "services.blog_api.features.feed_full_content".to_owned(),
+            env_flag(&value),
+        ));
+    }
+
+    for (name, key) in [
+        ("TOKEN_SECRET", "auth.token_secret"),
+        ("SERVICE_TOKEN", "auth.service_token"),
+        ("SITE_URL", "site.url"),
+        ("SITE_TITLE", "site.title"),
+        ("SITE_DESCRIPTION", "site.description"),
+    ] {
+        if let Some(value) = var(name) {
+            res.push((key.to_owned(), Value::String(value)));
+        }
+    }
+    for (name, key) in [
+        ("TOKEN_EXPIRE_SECS", "auth.token_expire"),
+        ("CACHE_TTL", "http.cache_ttl"),
+        ("CACHE_MAX_BYTES", "http.cache_max_bytes"),
+        ("VIEW_DEDUP_WINDOW", "views.dedup_window"),
+        ("VIEW_FLUSH_INTERVAL", "views.flush_interval"),
+        ("SPAM_MAX_LINKS", "spam.max_links"),
+    ] {
+        if let Some(value) = var(name) {
+            res.push((key.to_owned(), env_integer(name, &value)?));
+        }
+    }
+    if let Some(value) = var("SPAM_BAYES_THRESHOLD") {
+        let value = value
+            .trim()
+            .parse::<f64>()
+            .map_err(|_| anyhow!("SPAM_BAYES_THRESHOLD: invalid number \"{}\"", value))?;
+        res.push(("spam.bayes_threshold".to_owned(), Value::Float(value)));
+    }
+    if let Some(path) = var("SPAM_BLOCKLIST_FILE").filter(|path| !path.is_empty()) {
+        res.push(("spam.blocklist_file".to_owned(), Value::String(path)));
+    }
+    for (name, key) in [
+        ("ROBOTS_ALLOW", "site.robots_allow"),
+        ("ROBOTS_DISALLOW", "site.robots_disallow"),
+    ] {
+        if let Some(value) = var(name) {
+            res.push((key.to_owned(), env_list(&value)));
+        }
+    }
+    // 原来叫 RATE_LIMIT_TRUSTED_PROXIES
+    if let Some(value) = var("TRUSTED_PROXIES").or_else(|| var("RATE_LIMIT_TRUSTED_PROXIES")) {
+        res.push(("http.trusted_proxies".to_owned(), env_list(&value)));
+    }
+    // 逗号分隔的 路由名=请求数/秒数，如 new_comment=5/60,search_posts=30/60
+    if let Some(value) = var("RATE_LIMITS") {
+        let mut limits = Table::new();
+        for item in value.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let Some((route, limit)) = item.split_once('=') else {
+                return Err(anyhow!("RATE_LIMITS: invalid item \"{}\"", item));
+            };
+            limits.insert(
+                route.trim().to_owned(),
+                Value::String(limit.trim().to_owned()),
+            );
+        }
+        res.push(("http.rate_limits".to_owned(), Value::Table(limits)));
+    }
+    Ok(res)
+}
+
+/// env_overrides 环境变量覆盖的配置项，优先于配置文件
+fn env_overrides(vars: &HashMap<String, String>) -> Result<Vec<(String, Value)>> {
+    let var = |name: &str| vars.get(name).cloned();
+    let mut res = vec![];
+    for (name, key) in [
+        ("DB_MAX_CONNECTIONS", "max_connections"),
+        ("DB_MIN_CONNECTIONS", "min_connections"),
+        ("DB_CONNECT_TIMEOUT", "connect_timeout"),
+        ("DB_IDLE_TIMEOUT", "idle_timeout"),
+    ] {
+        if let Some(value) = var(name) {
+            let value = value
+                .parse::<i64>()
+                .map_err(|_| anyhow!("{}: invalid integer \"{}\"", name, value))?;
+            res.push((format!("database.{}", key), Value::Integer(value)));
+        }
+    }
+    // OpenTelemetry 的标准环境变量
+    if let Some(endpoint) = var("OTEL_EXPORTER_OTLP_ENDPOINT") {
+        res.push(("tracing.endpoint".to_owned(), Value::String(endpoint)));
+    }
+
+    for service in ALL_SERVICES {
+        let prefix = service.env_prefix();
+        let key = format!("services.{}", service.key());
+        for (suffix, field) in [
+            ("BIND", "bind"),
+            ("URL", "url"),
+            ("METRICS_BIND", "metrics"),
+            ("TLS_CERT", "tls.cert"),
+            ("TLS_KEY", "tls.key"),
+            ("TLS_CA", "tls.ca"),
+            ("TLS_DOMAIN", "tls.domain"),
+        ] {
+            if let Some(value) = var(&format!("{}_{}", prefix, suffix)) {
+                res.push((format!("{}.{}", key, field), Value::String(value)));
+            }
+        }
+    }
+
+    let mut generic = vars
+        .iter()
+        .filter_map(|(name, value)| {
+            let path = name
+                .strip_prefix(ENV_PREFIX)?
+                .to_lowercase()
+                .replace("__", ".");
+            Some((path, value.clone()))
+        })
+        .collect::<Vec<(String, String)>>();
+    generic.sort();
+    res.extend(
+        generic
+            .into_iter()
+            .map(|(path, value)| (path, parse_value(&value))),
+    );
+    Ok(res)
+}
+
+#[derive(Default)]
+struct CliArgs {
+    config: Option<PathBuf>,
+    sets: Vec<(String, Value)>,
+}
+
+/// parse_args 支持 --flag value 和 --flag=value 两种写法
+fn parse_args(srv: Option<Service>, args: &[String]) -> Result<CliArgs> {
+    let mut res = CliArgs::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_owned())),
+            _ => (arg.as_str(), None),
+        };
+        let mut value = || {
+            inline
+                .clone()
+                .or_else(|| args.next().cloned())
+                .ok_or_else(|| anyhow!("{} requires a value\n{}", flag, USAGE))
+        };
+        let service_key = || {
+            srv.map(|srv| format!("services.{}", srv.key()))
+                .ok_or_else(|| anyhow!("{} is only supported when starting a service", flag))
+        };
+        match flag {
+            "--config" => res.config = Some(PathBuf::from(value()?)),
+            "--bind" => res
+                .sets
+                .push((format!("{}.bind", service_key()?), Value::String(value()?))),
+            "--database-url" => res
+                .sets
+                .push(("database.url".to_owned(), Value::String(value()?))),
+            "--feature" | "--set" => {
+                let value = value()?;
+                let Some((key, item)) = value.split_once('=') else {
+                    return Err(anyhow!("{} expects <key>=<value>\n{}", flag, USAGE));
+                };
+                let key = if flag == "--feature" {
+                    format!("{}.features.{}", service_key()?, key)
+                } else {
+                    key.to_owned()
+                };
+                res.sets.push((key, parse_value(item)));
+            }
+            _ => return Err(anyhow!("unknown argument \"{}\"\n{}", arg, USAGE)),
+        }
+    }
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{env_overrides, legacy_env, parse_args, Config, RateLimit};
+    use crate::Service;
+    use std::collections::HashMap;
+    use std::net::IpAddr;
+    use std::time::Duration;
+
+    fn vars<const N: usize>(vars: [(&str, &str); N]) -> HashMap<String, String> {
+        vars.into_iter()
+            .map(|(name, value)| (name.to_owned(), value.to_owned()))
+            .collect()
+    }
+
+    #[test]
+    fn test_config_layers() {
+        let file = r#"
+            [database]
+            url = "postgres://localhost/file"
+            max_connections = 20
+
+            [services.post]
+            bind = "0.0.0.0:9000"
+            url = "http://post.internal:9000"
+        "#
+        .parse()
+        .unwrap();
+        let vars = vars([
+            ("DATABASE_URL", "postgres://localhost/legacy"),
+            ("TAG_SRV_PORT", "20000"),
+            ("COMMENT_REQUIRE_APPROVAL", "1"),
+            ("DB_MAX_CONNECTIONS", "30"),
+            ("SERVICE_TOKEN", "token"),
+            ("LILY__VIEWS__DEDUP_WINDOW", "60"),
+        ]);
+        let legacy = legacy_env(&vars).unwrap();
+        let env = env_overrides(&vars).unwrap();
+        let args = ["--bind", "[::]:7000", "--set=database.min_connections=5"].map(String::from);
+        let cli = parse_args(Some(Service::Post), &args).unwrap().sets;
+
+        let config =
+            Config::from_layers(Some(Service::Post), legacy, Some(file), env, cli).unwrap();
+        assert_eq!(config.database.url, "postgres://localhost/file");
+        assert_eq!(config.database.max_connections, 30);
+        assert_eq!(config.database.min_connections, 5);
+        assert_eq!(config.service(Service::Post).bind, "[::]:7000");
+        assert_eq!(
+            config.service(Service::Post).url(),
+            "http://post.internal:9000"
+        );
+        assert_eq!(config.service(Service::Tag).bind, "[::1]:20000");
+        assert_eq!(config.service(Service::Tag).url(), "http://[::1]:20000");
+        assert!(config.feature(Service::Comment, "require_approval"));
+        assert_eq!(config.views.dedup_window, 60);
+    }
+
+    #[test]
+    fn test_config_legacy_env() {
+        let file = r#"
+            [services.tag]
+            bind = "0.0.0.0:12345"
+            url = "http://tag.internal:12345"
+
+            [services.blog_api.features]
+            feed_full_content = true
+        "#
+        .parse()
+        .unwrap();
+        let vars = vars([
+            ("TAG_SRV_PORT", "20000"),
+            ("FEED_FULL_CONTENT", "false"),
+            ("DATABASE_URL", "postgres://localhost/legacy"),
+            ("SERVICE_TOKEN", "token"),
+        ]);
+        let legacy = legacy_env(&vars).unwrap();
+
+        let config =
+            Config::from_layers(Some(Service::Tag), legacy, Some(file), vec![], vec![]).unwrap();
+        assert_eq!(config.service(Service::Tag).bind, "0.0.0.0:12345");
+        assert!(config.feature(Service::BlogApi, "feed_full_content"));
+        assert_eq!(config.database.url, "postgres://localhost/legacy");
+    }
+
+    #[test]
+    fn test_config_legacy_settings() {
+        let env = vars([
+            ("TOKEN_SECRET", "secret"),
+            ("TOKEN_EXPIRE_SECS", "600"),
+            ("SERVICE_TOKEN", "token"),
+            ("SITE_URL", "https://blog.example.com/"),
+            ("ROBOTS_DISALLOW", "/api/, /admin/"),
+            ("RATE_LIMITS", "login=3/60"),
+            ("RATE_LIMIT_TRUSTED_PROXIES", "10.0.0.1"),
+            ("CACHE_TTL", "0"),
+            ("SPAM_BAYES_THRESHOLD", "0.8"),
+        ]);
+        let legacy = legacy_env(&env).unwrap();
+        let config =
+            Config::from_layers(Some(Service::BlogApi), legacy, None, vec![], vec![]).unwrap();
+        assert_eq!(config.auth.token_secret, "secret");
+        assert_eq!(config.auth.token_expire, 600);
+        assert_eq!(config.site.base_url(), "https://blog.example.com");
+        assert_eq!(config.site.robots_disallow, ["/api/", "/admin/"]);
+        assert_eq!(
+            config.http.rate_limits["login"],
+            RateLimit {
+                capacity: 3,
+                period: Duration::from_secs(60)
+            }
+        );
+        assert!(!config.http.rate_limits.contains_key("new_comment"));
+        assert_eq!(config.http.trusted_proxies, ["10.0.0.1".parse::<IpAddr>().unwrap()]);
+        assert_eq!(config.http.cache_ttl, 0);
+        assert_eq!(config.spam.bayes_threshold, 0.8);
+
+        // 格式错误的值在加载时就报错
+        let err = legacy_env(&vars([("CACHE_TTL", "30s")])).unwrap_err();
+        assert!(err.to_string().contains("CACHE_TTL"));
+        for (name, value) in [("RATE_LIMITS", "login=3"), ("TRUSTED_PROXIES", "proxy")] {
+            let legacy = legacy_env(&vars([(name, value)])).unwrap();
+            assert!(Config::from_layers(None, legacy, None, vec![], vec![]).is_err());
+        }
+        let err = Config::from_layers(Some(Service::BlogApi), vec![], None, vec![], vec![])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("auth.token_secret is required"));
+        assert!(err.contains("auth.service_token is required"));
+    }
+
+    #[test]
+    fn test_config_validate() {
+        let file = r#"
+            [database]
+            health_check_interval = 0
+
+            [client]
+            backoff_initial = 60
+
+            [tracing]
+            sample_ratio = 2.0
+
+            [services.comment]
+            bind = "localhost"
+
+            [services.comment.features]
+            require_aproval = true
+
+            [services.admin.tls]
+            cert = "cert.pem"
+        "#
+        .parse()
+        .unwrap();
+        let err = Config::from_layers(Some(Service::Comment), vec![], Some(file), vec![], vec![])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("database.url is required"));
+        assert!(err.contains("database.health_check_interval must be greater than 0"));
+        assert!(err.contains("client.backoff_initial must not exceed backoff_max"));
+        assert!(err.contains("tracing.sample_ratio must be between 0 and 1"));
+        assert!(err.contains("services.comment.bind: invalid socket address"));
+        assert!(err.contains("services.comment.features.require_aproval: unknown feature"));
+        assert!(err.contains("services.admin.tls: cert and key must be set together"));
+
+        let file = "[services.post]\nbnd = \"[::1]:1\"".parse().unwrap();
+        let err = Config::from_layers(None, vec![], Some(file), vec![], vec![]).unwrap_err();
+        assert!(err.to_string().contains("unknown field `bnd`"));
+        assert!(parse_args(Some(Service::Post), &["--port".to_owned()]).is_err());
+    }
+}
@@ -0,0 +1,80 @@
+// 列表的分页游标，对调用方不透明，文章和评论共用
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::sqlx::types::chrono::DateTime;
+
+/// 按时间排序时使用上一页最后一条的 (时间, id) 定位，其他排序方式按偏移量
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cursor {
+    /// newest 记录生成游标时的排序方向，继续翻页时要求一致
+    After {
+        newest: bool,
+        time: DateTimeWithTimeZone,
+        id: i32,
+    },
+    Offset(u64),
+}
+
+impl Cursor {
+    pub fn encode(&self) -> String {
+        let raw = match self {
+            Cursor::After { newest, time, id } => format!(
+                "{}:{}:{}",
+                if *newest { "d" } else { "a" },
+                time.timestamp_micros(),
+                id
+            ),
+            Cursor::Offset(offset) => format!("o:{}", offset),
+        };
+        URL_SAFE_NO_PAD.encode(raw)
+    }
+
+    pub fn decode(cursor: &str) -> Option<Self> {
+        let raw = String::from_utf8(URL_SAFE_NO_PAD.decode(cursor).ok()?).ok()?;
+        let mut parts = raw.split(':');
+        let res = match parts.next()? {
+            kind @ ("d" | "a") => {
+                let micros = parts.next()?.parse().ok()?;
+                Cursor::After {
+                    newest: kind == "d",
+                    time: DateTime::from_timestamp_micros(micros)?.fixed_offset(),
+                    id: parts.next()?.parse().ok()?,
+                }
+            }
+            "o" => Cursor::Offset(parts.next()?.parse().ok()?),
+            _ => return None,
+        };
+        match parts.next() {
+            Some(_) => None,
+            None => Some(res),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cursor::Cursor;
+    use sea_orm::sqlx::types::chrono::DateTime;
+
+    #[test]
+    fn test_cursor() {
+        for newest in [true, false] {
+            let after = Cursor::After {
+                newest,
+                time: DateTime::from_timestamp_micros(1729209600123456)
+                    .unwrap()
+                    .fixed_offset(),
+                id: 42,
+            };
+            assert_eq!(Cursor::decode(&after.encode()), Some(after));
+        }
+        assert_eq!(
+            Cursor::decode(&Cursor::Offset(20).encode()),
+            Some(Cursor::Offset(20))
+        );
+        assert_eq!(Cursor::decode("not a cursor"), None);
+        assert_eq!(Cursor::decode(""), None);
+    }
+}
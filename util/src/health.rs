@@ -0,0 +1,37 @@
+// grpc.health.v1.Health 健康检查：定时 ping 数据库，失败时服务状态为 NOT_SERVING
+
+use sea_orm::DatabaseConnection;
+use std::time::Duration;
+use tonic::server::NamedService;
+use tonic_health::pb::health_server::{Health, HealthServer};
+use tonic_health::ServingStatus;
+
+/// health_service 创建健康检查服务，同时更新服务 S 和整体（service 为空）的状态。
+/// 不经过 service token 校验，负载均衡器和 grpc_health_probe 可以直接调用
+pub fn health_service<S: NamedService>(
+    db: DatabaseConnection,
+    interval: Duration,
+) -> HealthServer<impl Health> {
+    let (mut reporter, service) = tonic_health::server::health_reporter();
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        let mut last = None;
+        loop {
+            ticker.tick().await;
+            let status = match db.ping().await {
+                Ok(()) => ServingStatus::Serving,
+                Err(err) => {
+                    tracing::error!("Database ping failed: {}", err);
+                    ServingStatus::NotServing
+                }
+            };
+            if last != Some(status) {
+                tracing::info!("{} health: {:?}", S::NAME, status);
+                reporter.set_service_status(S::NAME, status).await;
+                reporter.set_service_status("", status).await;
+                last = Some(status);
+            }
+        }
+    });
+    service
+}
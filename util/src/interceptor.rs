@@ -0,0 +1,147 @@
+// 服务之间调用的鉴权：调用方在 metadata 中携带 auth.service_token，服务端进行校验
+
+use crate::backoff::BackoffConnector;
+use crate::{config, telemetry, Service};
+use anyhow::{anyhow, Result};
+use hyper_util::client::legacy::connect::HttpConnector;
+use tonic::metadata::{Ascii, MetadataValue};
+use tonic::service::interceptor::InterceptedService;
+use tonic::service::Interceptor;
+use tonic::transport::Channel;
+use tonic::{Request, Status};
+
+/// 存放服务 token 的 metadata key
+pub const SERVICE_TOKEN_KEY: &str = "x-service-token";
+
+fn parse_token(token: &str) -> Result<MetadataValue<Ascii>> {
+    if token.is_empty() {
+        return Err(anyhow!("auth.service_token must not be empty"));
+    }
+    Ok(token.parse()?)
+}
+
+fn token_from_config() -> Result<MetadataValue<Ascii>> {
+    parse_token(&config::get()?.auth.service_token)
+}
+
+/// 服务端拦截器，拒绝没有携带正确 token 的调用
+#[derive(Clone)]
+pub struct CheckServiceToken {
+    token: MetadataValue<Ascii>,
+}
+
+impl CheckServiceToken {
+    pub fn new(token: &str) -> Result<Self> {
+        Ok(Self {
+            token: parse_token(token)?,
+        })
+    }
+
+    pub fn from_config() -> Result<Self> {
+        Ok(Self {
+            token: token_from_config()?,
+        })
+    }
+}
+
+impl Interceptor for CheckServiceToken {
+    fn call(&mut self, request: Request<()>) -> Result<Request<()>, Status> {
+        match request.metadata().get(SERVICE_TOKEN_KEY) {
+            Some(token) if constant_time_eq(token.as_bytes(), self.token.as_bytes()) => Ok(request),
+            Some(_) => Err(Status::unauthenticated("invalid service token")),
+            None => Err(Status::unauthenticated("missing service token")),
+        }
+    }
+}
+
+/// 客户端拦截器，为每个调用附上 token 和当前的 trace context
+#[derive(Clone)]
+pub struct AttachServiceToken {
+    token: MetadataValue<Ascii>,
+}
+
+impl AttachServiceToken {
+    pub fn new(token: &str) -> Result<Self> {
+        Ok(Self {
+            token: parse_token(token)?,
+        })
+    }
+
+    pub fn from_config() -> Result<Self> {
+        Ok(Self {
+            token: token_from_config()?,
+        })
+    }
+}
+
+impl Interceptor for AttachServiceToken {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        request
+            .metadata_mut()
+            .insert(SERVICE_TOKEN_KEY, self.token.clone());
+        telemetry::inject_context(request.metadata_mut());
+        Ok(request)
+    }
+}
+
+/// 会自动附上 token 的连接
+pub type AuthChannel = InterceptedService<Channel, AttachServiceToken>;
+
+/// connect 创建连接指定服务的 channel，用于创建各服务的 client。
+/// 第一次调用时才建立连接，断开后在下一次调用时重连，连续失败时按 client 配置退避
+pub fn connect(srv: Service) -> Result<AuthChannel> {
+    let config = config::get()?;
+    let client = &config.client;
+    let service = config.service(srv);
+    let mut endpoint = Channel::from_shared(service.url())?
+        .connect_timeout(client.connect_timeout())
+        .timeout(client.request_timeout())
+        .tcp_keepalive(Some(client.keepalive()))
+        .http2_keep_alive_interval(client.keepalive())
+        .keep_alive_while_idle(true);
+    if let Some(tls) = service.client_tls()? {
+        endpoint = endpoint.tls_config(tls)?;
+    }
+
+    let mut http = HttpConnector::new();
+    http.enforce_http(false);
+    http.set_nodelay(true);
+    http.set_keepalive(Some(client.keepalive()));
+    let connector = BackoffConnector::new(http, client.backoff_initial(), client.backoff_max());
+    let channel = endpoint.connect_with_connector_lazy(connector);
+    Ok(InterceptedService::new(
+        channel,
+        AttachServiceToken::from_config()?,
+    ))
+}
+
+/// 比较时间不依赖于第一个不同字节的位置
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_service_token() {
+        let mut check = CheckServiceToken::new("test_service_token").unwrap();
+        let mut attach = AttachServiceToken::new("test_service_token").unwrap();
+
+        let request = attach.call(Request::new(())).unwrap();
+        assert!(check.call(request).is_ok());
+
+        assert!(check.call(Request::new(())).is_err());
+
+        let mut request = Request::new(());
+        request
+            .metadata_mut()
+            .insert(SERVICE_TOKEN_KEY, "wrong".parse().unwrap());
+        assert!(check.call(request).is_err());
+        assert!(CheckServiceToken::new("").is_err());
+    }
+}
@@ -1,15 +1,27 @@
+pub mod backoff;
+pub mod config;
+pub mod cursor;
+pub mod health;
+pub mod interceptor;
+pub mod metrics;
 pub mod password;
+pub mod stats;
+pub mod telemetry;
+pub mod token;
 
 use anyhow::{anyhow, Result};
-use dotenv::dotenv;
+use metrics::GrpcMetricsLayer;
+use migration::{Migrator, MigratorTrait};
 use sea_orm::prelude::DateTimeWithTimeZone;
 use sea_orm::sqlx::types::chrono::{Local, TimeZone};
-use sea_orm::{Database, DatabaseConnection};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::Path;
-use std::{env, fs};
+use sea_orm::{ConnectOptions, Database, DatabaseConnection};
+use tonic::transport::Server;
+use tower_layer::{Identity, Stack};
 
+/// 站点地图协议规定单个文件最多的 URL 数，blog-api 按这个数量分文件，post-srv 按这个限制每页数量
+pub const SITEMAP_MAX_URLS: i32 = 50000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Service {
     Tag,
     Post,
@@ -18,81 +30,112 @@ pub enum Service {
     BlogApi,
 }
 
+/// get_db_connection 按 database 配置创建连接池
 pub async fn get_db_connection() -> Result<DatabaseConnection> {
-    dotenv().ok();
-    let database_url = env::var("DATABASE_URL")?;
-    let db = Database::connect(database_url).await?;
+    let config = &config::get()?.database;
+    if config.url.is_empty() {
+        return Err(anyhow!("database.url is required (DATABASE_URL)"));
+    }
+    let mut options = ConnectOptions::new(&config.url);
+    options
+        .max_connections(config.max_connections)
+        .min_connections(config.min_connections)
+        .connect_timeout(config.connect_timeout())
+        .idle_timeout(config.idle_timeout());
+    let mut db = Database::connect(options).await?;
+    metrics::instrument_db(&mut db);
     Ok(db)
 }
 
+/// database.auto_migrate 为 true 时，在服务启动时执行尚未应用的数据库迁移
+pub async fn apply_pending_migrations(db: &DatabaseConnection) -> Result<()> {
+    if config::get()?.database.auto_migrate {
+        Migrator::up(db, None).await?;
+    }
+    Ok(())
+}
+
+/// get_service_addr 服务的监听地址
 pub fn get_service_addr(srv: Service) -> Result<String> {
-    dotenv().ok();
-    let value = match srv {
-        Service::Tag => env::var("TAG_SRV_PORT")?,
-        Service::Post => env::var("POST_SRV_PORT")?,
-        Service::Comment => env::var("COMMENT_SRV_PORT")?,
-        Service::Admin => env::var("ADMIN_SRV_PORT")?,
-        Service::BlogApi => env::var("BLOG_API_PORT")?,
-    };
-    Ok(format!("[::1]:{}", value))
+    Ok(config::get()?.service(srv).bind.clone())
 }
 
+/// get_service_url 连接服务使用的地址
 pub fn get_service_url(srv: Service) -> Result<String> {
-    Ok(format!("http://{}", get_service_addr(srv)?))
+    Ok(config::get()?.service(srv).url())
 }
 
-/// gen_html 从 markdown 文件生成 HTML 放入 gen_html 文件夹中，返回路径
-pub fn gen_html(md_path: &str) -> Result<(String, i32)> {
-    let file_name = Path::new(md_path)
-        .file_stem()
-        .ok_or(anyhow!("No file name!"))?
-        .to_str()
-        .ok_or(anyhow!("File name failed from OsStr to str"))?;
-    let md = fs::read_to_string(md_path)?;
-    let html = markdown::to_html(&md);
-
-    // 存放路径
-    let mut path = env::current_dir()?;
-    path.push("gen_html");
-    // 检查是否存在这个文件夹
-    if path.read_dir().is_err() {
-        fs::create_dir(path.clone())?;
+/// 记录请求指标的 tonic 服务
+pub type GrpcServer = Server<Stack<GrpcMetricsLayer, Identity>>;
+
+/// grpc_server 创建 tonic 服务，配置了证书时启用 TLS
+pub fn grpc_server(srv: Service) -> Result<GrpcServer> {
+    let mut server = Server::builder().trace_fn(telemetry::grpc_span);
+    if let Some(tls) = config::get()?.service(srv).server_tls()? {
+        server = server.tls_config(tls)?;
+    }
+    Ok(server.layer(GrpcMetricsLayer::new(srv)))
+}
+
+/// shutdown_signal 收到 Ctrl+C 或 SIGTERM 时返回，用于 serve_with_shutdown，
+/// 服务停止接受新连接，等待处理中的请求完成后退出
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(err) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for Ctrl+C: {}", err);
+            std::future::pending::<()>().await;
+        }
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(err) => {
+                tracing::error!("Failed to listen for SIGTERM: {}", err);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
     }
-    path.push(format!("{file_name}.html"));
-
-    fs::write(path.clone(), html)?;
-    let cleaned_md = clean_markdown(&md);
-    let words_len = cleaned_md.chars().count() as i32;
-    // println!("{cleaned_md}");
-    Ok((
-        path.to_str()
-            .ok_or(anyhow!("Path to_str() err!"))?
-            .to_string(),
-        words_len,
-    ))
+    tracing::info!("Shutdown signal received, waiting for in-flight requests");
 }
 
-/// get_summary 获取 md_path 路径文章的前 200 字
-pub fn get_summary(md_path: &str) -> Result<String> {
-    let file = File::open(md_path)?;
-    let reader = BufReader::new(file);
+/// gen_html 将 markdown 内容转换为 HTML，同时返回字数
+pub fn gen_html(md: &str) -> (String, i32) {
+    let html = markdown::to_html(md);
+    let words_len = clean_markdown(md).chars().count() as i32;
+    (html, words_len)
+}
+
+/// get_summary 获取 markdown 内容的前 200 字
+pub fn get_summary(md: &str) -> String {
     let mut res = String::new();
-    for line in reader.lines() {
-        let line = line?;
-        let cleaned_line = clean_markdown(&line);
-        if res.len() >= 200 {
+    let mut res_len = 0;
+    for line in md.lines() {
+        let cleaned_line = clean_markdown(line);
+        let line_len = cleaned_line.chars().count();
+        if res_len >= 200 {
             break;
-        } else if res.len() + cleaned_line.len() > 200 {
-            let additional_chars_needed = 200 - res.len();
-            res.push_str(&cleaned_line[..additional_chars_needed]);
+        } else if res_len + line_len > 200 {
+            res.extend(cleaned_line.chars().take(200 - res_len));
             break;
         } else {
             res.push_str(&cleaned_line);
+            res_len += line_len;
         }
         res.push(' '); // 每行结尾放个空格
+        res_len += 1;
     }
 
-    Ok(res)
+    res
 }
 
 fn clean_markdown(text: &str) -> String {
@@ -140,6 +183,10 @@ pub fn datetime_conversion(dt: Option<DateTimeWithTimeZone>) -> Option<prost_typ
     }
 }
 
+pub fn i64_to_timestamp(i: Option<i64>) -> Option<prost_types::Timestamp> {
+    i.map(|seconds| prost_types::Timestamp { seconds, nanos: 0 })
+}
+
 pub fn i64_to_dateline_range(i: Option<(i64, i64)>) -> Option<proto::DatelineRange> {
     match i {
         Some(i) => {
@@ -160,53 +207,25 @@ pub fn i64_to_dateline_range(i: Option<(i64, i64)>) -> Option<proto::DatelineRan
     }
 }
 
-pub fn tags_to_u8(tags: Vec<i32>) -> Vec<u8> {
-    // 数据库中使用 X1X 进行间隔存储，比如 X1XX2X 进行查询时直接查询 X1X 即可
-    // （防止只使用一个间隔符号，例如 11X12X 查询 1X 出错）
-    use std::fmt::Write;
-    tags.iter()
-        .fold(String::new(), |mut str, num| {
-            let _ = write!(str, "X{num}X");
-            str
-        })
-        .into_bytes()
-}
-
-pub fn u8_to_tags(bytes: Vec<u8>) -> Vec<i32> {
-    // 将 Vec<u8> 转换为 String
-    let string = String::from_utf8(bytes).unwrap_or("".to_string()); // 直接忽略算了
-
-    // 分割字符串并过滤空字符串
-    string
-        .split('X')
-        .filter_map(|part| part.parse::<i32>().ok())
-        .collect()
-}
-
 #[cfg(test)]
 mod tests {
-    use crate::{gen_html, get_summary, tags_to_u8, u8_to_tags};
+    use crate::{gen_html, get_summary};
 
     #[test]
     fn test_gen_html() {
-        let md_path = "/Users/cakeal/Desktop/vsc/lily-blog/README.md";
-        let res = gen_html(md_path);
-        dbg!(res.unwrap());
+        let (html, words_len) = gen_html("# Lily Blog\n\n一个使用 Rust 的微服务的博客。");
+        assert_eq!(
+            html,
+            "<h1>Lily Blog</h1>\n<p>一个使用 Rust 的微服务的博客。</p>"
+        );
+        assert_eq!(words_len, 24);
     }
 
     #[test]
     fn test_get_summary() {
-        let md_path = "/Users/cakeal/Desktop/vsc/lily-blog/README.md";
-        let res = get_summary(md_path);
-        dbg!(res.unwrap());
-    }
-
-    #[test]
-    fn test_u8_to_tags() {
-        let tags = vec![1, 2, 3, 4, 5];
-        let bytes = tags_to_u8(tags.clone());
-        dbg!(String::from_utf8(bytes.clone()).unwrap());
-        let reformed_tags = u8_to_tags(bytes);
-        assert_eq!(tags, reformed_tags);
+        let md = "# Lily Blog\n\n".to_string() + &"博客".repeat(200);
+        let res = get_summary(&md);
+        assert!(res.starts_with("LilyBlog  博客"));
+        assert_eq!(res.chars().count(), 200);
     }
 }
@@ -0,0 +1,477 @@
+// Prometheus 指标：gRPC 和 HTTP 请求数、耗时、错误码，数据库查询耗时和连接池使用情况
+
+use crate::{config, Service};
+use anyhow::Result;
+use axum::extract::{MatchedPath, Request};
+use axum::http::{header, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
+    TextEncoder,
+};
+use sea_orm::sqlx::PgPool;
+use sea_orm::DatabaseConnection;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::OnceLock;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tonic::codegen::http;
+use tonic::Code;
+use tower_layer::Layer;
+use tower_service::Service as TowerService;
+
+static METRICS: OnceLock<Metrics> = OnceLock::new();
+
+/// 每个 gRPC 服务都提供的健康检查服务
+const HEALTH_SERVICE: &str = "grpc.health.v1.Health";
+const HEALTH_METHODS: &[&str] = &["Check", "Watch"];
+/// 不认识的服务和方法都记为这个，避免 label 的取值随请求路径无限增长
+const UNKNOWN: &str = "unknown";
+
+struct Metrics {
+    registry: Registry,
+    grpc_requests: IntCounterVec,
+    grpc_duration: HistogramVec,
+    http_requests: IntCounterVec,
+    http_duration: HistogramVec,
+    db_queries: IntCounterVec,
+    db_duration: HistogramVec,
+    db_pool: IntGaugeVec,
+    db_pool_max: IntGauge,
+    pool: OnceLock<PgPool>, // 采集时读取连接池的状态
+}
+
+impl Metrics {
+    fn new() -> prometheus::Result<Self> {
+        let registry = Registry::new();
+        let grpc_requests = IntCounterVec::new(
+            Opts::new("grpc_server_requests_total", "gRPC 调用次数"),
+            &["grpc_service", "grpc_method", "grpc_code"],
+        )?;
+        let grpc_duration = HistogramVec::new(
+            HistogramOpts::new("grpc_server_request_duration_seconds", "gRPC 调用耗时"),
+            &["grpc_service", "grpc_method"],
+        )?;
+        let http_requests = IntCounterVec::new(
+            Opts::new("http_requests_total", "HTTP 请求数"),
+            &["method", "route", "status"],
+        )?;
+        let http_duration = HistogramVec::new(
+            HistogramOpts::new("http_request_duration_seconds", "HTTP 请求耗时"),
+            &["method", "route"],
+        )?;
+        let db_queries = IntCounterVec::new(
+            Opts::new("db_queries_total", "数据库查询次数"),
+            &["operation", "result"],
+        )?;
+        let db_duration = HistogramVec::new(
+            HistogramOpts::new("db_query_duration_seconds", "数据库查询耗时"),
+            &["operation"],
+        )?;
+        let db_pool = IntGaugeVec::new(
+            Opts::new("db_pool_connections", "连接池中的连接数"),
+            &["state"],
+        )?;
+        let db_pool_max = IntGauge::new("db_pool_max_connections", "连接池的最大连接数")?;
+        registry.register(Box::new(grpc_requests.clone()))?;
+        registry.register(Box::new(grpc_duration.clone()))?;
+        registry.register(Box::new(http_requests.clone()))?;
+        registry.register(Box::new(http_duration.clone()))?;
+        registry.register(Box::new(db_queries.clone()))?;
+        registry.register(Box::new(db_duration.clone()))?;
+        registry.register(Box::new(db_pool.clone()))?;
+        Ok(Self {
+            registry,
+            grpc_requests,
+            grpc_duration,
+            http_requests,
+            http_duration,
+            db_queries,
+            db_duration,
+            db_pool,
+            db_pool_max,
+            pool: OnceLock::new(),
+        })
+    }
+}
+
+fn metrics() -> &'static Metrics {
+    METRICS.get_or_init(|| Metrics::new().expect("metric names are valid"))
+}
+
+/// render 以 Prometheus 文本格式输出全部指标
+pub fn render() -> Result<String> {
+    let metrics = metrics();
+    if let Some(pool) = metrics.pool.get() {
+        let (size, idle) = (pool.size() as i64, pool.num_idle() as i64);
+        metrics.db_pool.with_label_values(&["idle"]).set(idle);
+        metrics
+            .db_pool
+            .with_label_values(&["active"])
+            .set(size - idle);
+        metrics
+            .db_pool_max
+            .set(pool.options().get_max_connections() as i64);
+    }
+    let mut buffer = vec![];
+    TextEncoder::new().encode(&metrics.registry.gather(), &mut buffer)?;
+    Ok(String::from_utf8(buffer)?)
+}
+
+/// metrics_handler /metrics 接口
+pub async fn metrics_handler() -> Response {
+    match render() {
+        Ok(body) => ([(header::CONTENT_TYPE, prometheus::TEXT_FORMAT)], body).into_response(),
+        Err(err) => {
+            tracing::error!("Failed to render metrics: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// serve_metrics 在服务配置的 metrics 地址上提供 /metrics，没有配置时不启动
+pub async fn serve_metrics(srv: Service) -> Result<()> {
+    let Some(addr) = config::get()?.service(srv).metrics_bind() else {
+        return Ok(());
+    };
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    tracing::info!("Metrics listening on http://{}/metrics", addr);
+    tokio::spawn(async move {
+        let app = Router::new().route("/metrics", get(metrics_handler));
+        if let Err(err) = axum::serve(listener, app).await {
+            tracing::error!("Metrics server failed: {}", err);
+        }
+    });
+    Ok(())
+}
+
+/// instrument_db 记录每次查询的耗时，并在采集时读取连接池状态
+pub fn instrument_db(db: &mut DatabaseConnection) {
+    let metrics = metrics();
+    db.set_metric_callback(move |info| {
+        let operation = query_operation(&info.statement.sql);
+        let result = if info.failed { "error" } else { "ok" };
+        metrics
+            .db_queries
+            .with_label_values(&[operation, result])
+            .inc();
+        metrics
+            .db_duration
+            .with_label_values(&[operation])
+            .observe(info.elapsed.as_secs_f64());
+    });
+    // 没有数据库的进程（blog-api）不输出连接池上限
+    if metrics
+        .pool
+        .set(db.get_postgres_connection_pool().clone())
+        .is_ok()
+    {
+        let _ = metrics
+            .registry
+            .register(Box::new(metrics.db_pool_max.clone()));
+    }
+}
+
+/// query_operation SQL 语句的类型，作为 label 不能直接使用语句本身
+fn query_operation(sql: &str) -> &'static str {
+    let keyword = sql
+        .trim_start_matches(|c: char| c.is_whitespace() || c == '(')
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .next()
+        .unwrap_or_default();
+    match keyword.to_ascii_uppercase().as_str() {
+        "SELECT" | "WITH" => "select",
+        "INSERT" => "insert",
+        "UPDATE" => "update",
+        "DELETE" => "delete",
+        "BEGIN" | "COMMIT" | "ROLLBACK" | "SAVEPOINT" | "RELEASE" => "transaction",
+        _ => "other",
+    }
+}
+
+/// track_http blog-api 的 HTTP 指标中间件，route 为路由模板，未匹配的请求记为 unmatched
+pub async fn track_http(request: Request, next: Next) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_owned())
+        .unwrap_or_else(|| "unmatched".to_owned());
+    let method = request.method().to_string();
+    let start = Instant::now();
+    let response = next.run(request).await;
+    let metrics = metrics();
+    metrics
+        .http_requests
+        .with_label_values(&[&method, &route, response.status().as_str()])
+        .inc();
+    metrics
+        .http_duration
+        .with_label_values(&[&method, &route])
+        .observe(start.elapsed().as_secs_f64());
+    response
+}
+
+impl Service {
+    /// grpc_name 服务的 gRPC 全名，同生成代码中的 NamedService::NAME
+    fn grpc_name(&self) -> &'static str {
+        match self {
+            Service::Tag => "tag.TagService",
+            Service::Post => "post.PostService",
+            Service::Comment => "comment.CommentService",
+            Service::Admin => "admin.AdminService",
+            Service::BlogApi => UNKNOWN,
+        }
+    }
+
+    /// grpc_methods 服务提供的全部方法，与 proto 文件中的 rpc 一致
+    fn grpc_methods(&self) -> &'static [&'static str] {
+        match self {
+            Service::Tag => &[
+                "CreateTag",
+                "EditTag",
+                "ListTags",
+                "ToggleTag",
+                "TagExists",
+                "GetTagInfo",
+            ],
+            Service::Post => &[
+                "CreatePost",
+                "EditPost",
+                "ListPosts",
+                "TogglePost",
+                "GetPost",
+                "ListPostRevisions",
+                "DiffPostRevisions",
+                "RestorePostRevision",
+                "ListPostSitemap",
+                "GetDailyViews",
+                "ListTopPosts",
+                "CountTagPosts",
+                "RecordPostView",
+            ],
+            Service::Comment => &[
+                "CreateComment",
+                "GetPostComments",
+                "ToggleComment",
+                "ListComments",
+                "SetCommentsStatus",
+                "GetDailyComments",
+            ],
+            Service::Admin => &[
+                "CreateAdmin",
+                "ListAdmin",
+                "EditAdmin",
+                "ToggleAdmin",
+                "AdminExists",
+                "GetAdmin",
+            ],
+            Service::BlogApi => &[],
+        }
+    }
+}
+
+/// grpc_labels 按 /package.Service/Method 拆分出服务名和方法名，只接受这个进程提供的服务和方法，
+/// 其他的都记为 unknown。路径由调用方决定，未通过认证的调用也会被记录，不能直接作为 label
+fn grpc_labels(srv: Service, path: &str, code: Code) -> (&'static str, &'static str) {
+    let Some((service, method)) = path.trim_start_matches('/').split_once('/') else {
+        return (UNKNOWN, UNKNOWN);
+    };
+    let (service, methods) = if service == srv.grpc_name() {
+        (srv.grpc_name(), srv.grpc_methods())
+    } else if service == HEALTH_SERVICE {
+        (HEALTH_SERVICE, HEALTH_METHODS)
+    } else {
+        return (UNKNOWN, UNKNOWN);
+    };
+    match methods.iter().find(|known| **known == method) {
+        Some(method) if code != Code::Unimplemented => (service, method),
+        _ => (UNKNOWN, UNKNOWN),
+    }
+}
+
+/// observe_grpc 记录一次 gRPC 调用
+fn observe_grpc(srv: Service, path: &str, code: Code, elapsed: Duration) {
+    let (service, method) = grpc_labels(srv, path, code);
+    let metrics = metrics();
+    metrics
+        .grpc_requests
+        .with_label_values(&[service, method, &format!("{:?}", code)])
+        .inc();
+    metrics
+        .grpc_duration
+        .with_label_values(&[service, method])
+        .observe(elapsed.as_secs_f64());
+}
+
+/// tonic 服务的指标 layer，由 grpc_server 添加
+#[derive(Debug, Clone, Copy)]
+pub struct GrpcMetricsLayer {
+    srv: Service,
+}
+
+impl GrpcMetricsLayer {
+    pub fn new(srv: Service) -> Self {
+        Self { srv }
+    }
+}
+
+impl<S> Layer<S> for GrpcMetricsLayer {
+    type Service = GrpcMetrics<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        GrpcMetrics {
+            inner,
+            srv: self.srv,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GrpcMetrics<S> {
+    inner: S,
+    srv: Service,
+}
+
+impl<S, B, RB> TowerService<http::Request<B>> for GrpcMetrics<S>
+where
+    S: TowerService<http::Request<B>, Response = http::Response<RB>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let path = request.uri().path().to_owned();
+        let srv = self.srv;
+        let start = Instant::now();
+        let response = self.inner.call(request);
+        Box::pin(async move {
+            let response = response.await;
+            // 返回错误时 tonic 把状态放在响应头中，成功时状态在 trailers 中，总是 OK
+            let code = match &response {
+                Ok(response) => response
+                    .headers()
+                    .get("grpc-status")
+                    .map(|status| Code::from_bytes(status.as_bytes()))
+                    .unwrap_or(Code::Ok),
+                Err(_) => Code::Unknown,
+            };
+            observe_grpc(srv, &path, code, start.elapsed());
+            response
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::metrics::{grpc_labels, observe_grpc, query_operation, render};
+    use crate::Service;
+    use std::time::Duration;
+    use tonic::Code;
+
+    #[test]
+    fn test_query_operation() {
+        assert_eq!(
+            query_operation("\n  SELECT \"post\".\"id\" FROM \"post\""),
+            "select"
+        );
+        assert_eq!(query_operation("(SELECT 1) UNION (SELECT 2)"), "select");
+        assert_eq!(query_operation("insert into tag"), "insert");
+        assert_eq!(query_operation("COMMIT"), "transaction");
+        assert_eq!(query_operation(""), "other");
+    }
+
+    #[test]
+    fn test_render_grpc_metrics() {
+        observe_grpc(
+            Service::Tag,
+            "/tag.TagService/GetTagInfo",
+            Code::NotFound,
+            Duration::from_millis(3),
+        );
+        let text = render().unwrap();
+        assert!(text.contains(
+            "grpc_server_requests_total{grpc_code=\"NotFound\",grpc_method=\"GetTagInfo\",grpc_service=\"tag.TagService\"} 1"
+        ));
+        assert!(text.contains("grpc_server_request_duration_seconds_bucket"));
+    }
+
+    #[test]
+    fn test_grpc_labels() {
+        let labels = |path, code| grpc_labels(Service::Post, path, code);
+        assert_eq!(
+            labels("/post.PostService/GetPost", Code::Ok),
+            ("post.PostService", "GetPost")
+        );
+        assert_eq!(
+            labels("/grpc.health.v1.Health/Check", Code::Ok),
+            ("grpc.health.v1.Health", "Check")
+        );
+        assert_eq!(
+            labels("/post.PostService/NoSuchMethod", Code::Unimplemented),
+            ("unknown", "unknown")
+        );
+        assert_eq!(
+            labels("/tag.TagService/GetTagInfo", Code::Ok),
+            ("unknown", "unknown")
+        );
+        assert_eq!(labels("/random", Code::Ok), ("unknown", "unknown"));
+    }
+
+    #[test]
+    fn test_grpc_labels_unauthenticated() {
+        // 没有携带 token 的调用在路由之前就被拒绝，不会返回 Unimplemented
+        assert_eq!(
+            grpc_labels(
+                Service::Post,
+                "/post.PostService/x7Kq2Random",
+                Code::Unauthenticated
+            ),
+            ("unknown", "unknown")
+        );
+        observe_grpc(
+            Service::Comment,
+            "/comment.CommentService/y9Random",
+            Code::Unauthenticated,
+            Duration::from_millis(1),
+        );
+        let text = render().unwrap();
+        assert!(!text.contains("y9Random"));
+        assert!(text.contains(
+            "grpc_server_requests_total{grpc_code=\"Unauthenticated\",grpc_method=\"unknown\",grpc_service=\"unknown\"}"
+        ));
+    }
+
+    #[test]
+    fn test_grpc_methods_match_proto() {
+        for (srv, proto) in [
+            (Service::Tag, include_str!("../../proto/proto/tag.proto")),
+            (Service::Post, include_str!("../../proto/proto/post.proto")),
+            (
+                Service::Comment,
+                include_str!("../../proto/proto/comment.proto"),
+            ),
+            (
+                Service::Admin,
+                include_str!("../../proto/proto/admin.proto"),
+            ),
+        ] {
+            let methods = proto
+                .lines()
+                .filter_map(|line| line.trim().strip_prefix("rpc "))
+                .filter_map(|line| line.split('(').next())
+                .map(str::trim)
+                .collect::<Vec<&str>>();
+            assert_eq!(methods, srv.grpc_methods());
+        }
+    }
+}
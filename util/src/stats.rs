@@ -0,0 +1,80 @@
+// 统计接口共用的日期处理
+
+use chrono::{Days, Local, NaiveDate};
+use std::collections::HashMap;
+
+const DEFAULT_DAYS: u64 = 30; // 没有指定开始日期时统计的天数
+const MAX_DAYS: i64 = 366; // 一次最多统计的天数
+
+/// date_range 解析 YYYY-MM-DD 格式的日期区间，包含两端，为空时 end 为今天，start 为 end 之前 30 天
+pub fn date_range(start: &str, end: &str) -> Result<(NaiveDate, NaiveDate), String> {
+    let end = match end {
+        "" => Local::now().date_naive(),
+        end => parse_date(end)?,
+    };
+    let start = match start {
+        "" => end - Days::new(DEFAULT_DAYS - 1),
+        start => parse_date(start)?,
+    };
+    if start > end {
+        return Err("start is after end".into());
+    }
+    if (end - start).num_days() >= MAX_DAYS {
+        return Err(format!("date range is longer than {} days", MAX_DAYS));
+    }
+    Ok((start, end))
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| format!("invalid date: {}", date))
+}
+
+/// fill_days 按日期从早到晚列出区间内每一天的数量，没有数据的日期为 0
+pub fn fill_days(
+    start: NaiveDate,
+    end: NaiveDate,
+    counts: &HashMap<NaiveDate, i64>,
+) -> Vec<(String, i64)> {
+    start
+        .iter_days()
+        .take_while(|day| *day <= end)
+        .map(|day| {
+            (
+                day.format("%Y-%m-%d").to_string(),
+                counts.get(&day).copied().unwrap_or(0),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stats::{date_range, fill_days};
+    use chrono::NaiveDate;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_date_range() {
+        let (start, end) = date_range("", "2026-10-18").unwrap();
+        assert_eq!(start, NaiveDate::from_ymd_opt(2026, 9, 19).unwrap());
+        assert_eq!(end, NaiveDate::from_ymd_opt(2026, 10, 18).unwrap());
+        assert!(date_range("2026-10-19", "2026-10-18").is_err());
+        assert!(date_range("2026-1-1x", "").is_err());
+        assert!(date_range("2025-01-01", "2026-10-18").is_err());
+    }
+
+    #[test]
+    fn test_fill_days() {
+        let start = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        let end = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        let counts = HashMap::from([(start, 3)]);
+        assert_eq!(
+            fill_days(start, end, &counts),
+            vec![
+                ("2026-10-16".to_owned(), 3),
+                ("2026-10-17".to_owned(), 0),
+                ("2026-10-18".to_owned(), 0)
+            ]
+        );
+    }
+}
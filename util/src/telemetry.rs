@@ -0,0 +1,286 @@
+// 日志和 OpenTelemetry 链路追踪：trace context 通过 HTTP 头和 gRPC metadata 在服务之间传递
+
+use crate::config::{self, Config, TraceExporter, TracingConfig};
+use crate::Service;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use opentelemetry::propagation::{Extractor, Injector};
+use opentelemetry::trace::TraceError;
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::{global, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::export::trace::{ExportResult, SpanData, SpanExporter};
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{Sampler, TracerProvider};
+use opentelemetry_sdk::{runtime, Resource};
+use serde_json::{json, Map, Value as JsonValue};
+use std::fs::{File, OpenOptions};
+use std::future::Future;
+use std::io::Write;
+use std::pin::Pin;
+use tonic::codegen::http;
+use tonic::metadata::{MetadataKey, MetadataMap, MetadataValue};
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+/// init 加载配置并初始化日志，配置了 exporter 时同时导出 span。
+/// 配置有误时仍会初始化日志，再返回错误，由调用方记录
+pub fn init(srv: Service) -> Result<&'static Config> {
+    let config = config::init(srv);
+    let provider = match &config {
+        Ok(config) => tracer_provider(srv, &config.tracing),
+        Err(_) => Ok(None),
+    };
+    let (provider, err) = match provider {
+        Ok(provider) => (provider, None),
+        Err(err) => (None, Some(err)),
+    };
+    let otel = provider.map(|provider| {
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        let tracer = provider.tracer(service_name(srv));
+        global::set_tracer_provider(provider);
+        tracing_opentelemetry::layer().with_tracer(tracer)
+    });
+    tracing_subscriber::registry()
+        .with(LevelFilter::INFO)
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_target(false)
+                .compact(),
+        )
+        .with(otel)
+        .init();
+    match err {
+        Some(err) => Err(err),
+        None => config,
+    }
+}
+
+/// shutdown 退出前导出还没有发送的 span
+pub async fn shutdown() {
+    let _ = tokio::task::spawn_blocking(global::shutdown_tracer_provider).await;
+}
+
+fn service_name(srv: Service) -> &'static str {
+    match srv {
+        Service::Tag => "tag-srv",
+        Service::Post => "post-srv",
+        Service::Comment => "comment-srv",
+        Service::Admin => "admin-srv",
+        Service::BlogApi => "blog-api",
+    }
+}
+
+fn tracer_provider(srv: Service, config: &TracingConfig) -> Result<Option<TracerProvider>> {
+    let provider = match config.exporter {
+        TraceExporter::None => return Ok(None),
+        TraceExporter::Otlp => {
+            let exporter = opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(&config.endpoint);
+            build_provider(srv, config, exporter.build_span_exporter()?)
+        }
+        TraceExporter::Stdout => {
+            build_provider(srv, config, opentelemetry_stdout::SpanExporter::default())
+        }
+        TraceExporter::File => {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&config.file)?;
+            build_provider(srv, config, FileExporter { srv, file })
+        }
+    };
+    Ok(Some(provider))
+}
+
+fn build_provider(
+    srv: Service,
+    config: &TracingConfig,
+    exporter: impl SpanExporter + 'static,
+) -> TracerProvider {
+    let sampler = Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(config.sample_ratio)));
+    let resource = Resource::new([KeyValue::new("service.name", service_name(srv))]);
+    TracerProvider::builder()
+        .with_batch_exporter(exporter, runtime::Tokio)
+        .with_config(
+            opentelemetry_sdk::trace::Config::default()
+                .with_sampler(sampler)
+                .with_resource(resource),
+        )
+        .build()
+}
+
+/// 每个 span 一行 JSON 追加写入文件，本地调试用
+#[derive(Debug)]
+struct FileExporter {
+    srv: Service,
+    file: File,
+}
+
+impl SpanExporter for FileExporter {
+    fn export(
+        &mut self,
+        batch: Vec<SpanData>,
+    ) -> Pin<Box<dyn Future<Output = ExportResult> + Send + 'static>> {
+        let mut lines = String::new();
+        for span in batch {
+            let line = json!({
+                "service": service_name(self.srv),
+                "trace_id": span.span_context.trace_id().to_string(),
+                "span_id": span.span_context.span_id().to_string(),
+                "parent_span_id": span.parent_span_id.to_string(),
+                "name": span.name,
+                "kind": format!("{:?}", span.span_kind),
+                "start": DateTime::<Utc>::from(span.start_time).to_rfc3339(),
+                "duration_us": span
+                    .end_time
+                    .duration_since(span.start_time)
+                    .unwrap_or_default()
+                    .as_micros() as u64,
+                "status": format!("{:?}", span.status),
+                "attributes": attributes(&span.attributes),
+                "events": span
+                    .events
+                    .iter()
+                    .map(|event| json!({
+                        "name": event.name,
+                        "time": DateTime::<Utc>::from(event.timestamp).to_rfc3339(),
+                        "attributes": attributes(&event.attributes),
+                    }))
+                    .collect::<Vec<JsonValue>>(),
+            });
+            lines.push_str(&line.to_string());
+            lines.push('\n');
+        }
+        let result = self
+            .file
+            .write_all(lines.as_bytes())
+            .map_err(|err| TraceError::Other(Box::new(err)));
+        Box::pin(std::future::ready(result))
+    }
+}
+
+fn attributes(attributes: &[KeyValue]) -> JsonValue {
+    attributes
+        .iter()
+        .map(|kv| (kv.key.to_string(), JsonValue::from(kv.value.to_string())))
+        .collect::<Map<String, JsonValue>>()
+        .into()
+}
+
+struct HeaderExtractor<'a>(&'a http::HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|key| key.as_str()).collect()
+    }
+}
+
+struct MetadataInjector<'a>(&'a mut MetadataMap);
+
+impl Injector for MetadataInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        if let (Ok(key), Ok(value)) = (
+            MetadataKey::from_bytes(key.as_bytes()),
+            MetadataValue::try_from(value),
+        ) {
+            self.0.insert(key, value);
+        }
+    }
+}
+
+/// inject_context 把当前 span 的 trace context 写入调用的 metadata
+pub fn inject_context(metadata: &mut MetadataMap) {
+    let context = Span::current().context();
+    global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&context, &mut MetadataInjector(metadata))
+    });
+}
+
+/// set_remote_parent 以请求头中的 trace context 作为 span 的父节点
+fn set_remote_parent(span: &Span, headers: &http::HeaderMap) {
+    let parent =
+        global::get_text_map_propagator(|propagator| propagator.extract(&HeaderExtractor(headers)));
+    span.set_parent(parent);
+}
+
+/// grpc_span tonic 服务端每个调用的 span，由 grpc_server 通过 trace_fn 设置
+pub fn grpc_span(request: &http::Request<()>) -> Span {
+    let path = request.uri().path();
+    let (service, method) = path
+        .trim_start_matches('/')
+        .split_once('/')
+        .unwrap_or_default();
+    let span = tracing::info_span!(
+        "grpc",
+        otel.name = path,
+        otel.kind = "server",
+        rpc.system = "grpc",
+        rpc.service = service,
+        rpc.method = method,
+    );
+    set_remote_parent(&span, request.headers());
+    span
+}
+
+/// http_span blog-api 每个请求的 span，用于 TraceLayer::make_span_with，
+/// 请求来自公网，不采用客户端传来的 traceparent，每个请求都是新的 trace
+pub fn http_span<B>(request: &http::Request<B>) -> Span {
+    let route = request
+        .extensions()
+        .get::<axum::extract::MatchedPath>()
+        .map(|path| path.as_str())
+        .unwrap_or("unmatched");
+    let span = tracing::info_span!(
+        "request",
+        method = %request.method(),
+        uri = %request.uri(),
+        version = ?request.version(),
+        otel.name = format!("{} {}", request.method(), route),
+        otel.kind = "server",
+    );
+    span
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::telemetry::{HeaderExtractor, MetadataInjector};
+    use opentelemetry::propagation::TextMapPropagator;
+    use opentelemetry::trace::{
+        SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState,
+    };
+    use opentelemetry::Context;
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
+    use tonic::metadata::MetadataMap;
+
+    #[test]
+    fn test_propagate_through_metadata() {
+        let span_context = SpanContext::new(
+            TraceId::from_hex("4bf92f3577b34da6a3ce929b0e0e4736").unwrap(),
+            SpanId::from_hex("00f067aa0ba902b7").unwrap(),
+            TraceFlags::SAMPLED,
+            true,
+            TraceState::default(),
+        );
+        let context = Context::new().with_remote_span_context(span_context.clone());
+        let propagator = TraceContextPropagator::new();
+        let mut metadata = MetadataMap::new();
+        propagator.inject_context(&context, &mut MetadataInjector(&mut metadata));
+        assert_eq!(
+            metadata.get("traceparent").unwrap(),
+            "00-4bf92f3577b34da6a3ce929b0e0e4736-00f067aa0ba902b7-01"
+        );
+
+        let headers = metadata.into_headers();
+        let extracted = propagator.extract(&HeaderExtractor(&headers));
+        assert_eq!(extracted.span().span_context(), &span_context);
+    }
+}
@@ -0,0 +1,73 @@
+use crate::config;
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Claims {
+    pub sub: i32,      // 管理员 id
+    pub email: String, // 管理员 email
+    pub iat: u64,      // 签发时间
+    pub exp: u64,      // 过期时间
+}
+
+/// issue 为登录成功的管理员签发 token，密钥和有效期见 auth 配置
+pub fn issue(admin_id: i32, email: &str) -> Result<String, String> {
+    let auth = &config::get().map_err(|err| err.to_string())?.auth;
+    issue_with(&auth.token_secret, auth.token_expire(), admin_id, email)
+}
+
+/// verify 校验签名与有效期，成功则返回其中的信息
+pub fn verify(token: &str) -> Result<Claims, String> {
+    let auth = &config::get().map_err(|err| err.to_string())?.auth;
+    verify_with(&auth.token_secret, token)
+}
+
+fn issue_with(
+    secret: &str,
+    expire: Duration,
+    admin_id: i32,
+    email: &str,
+) -> Result<String, String> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|err| err.to_string())?
+        .as_secs();
+    let claims = Claims {
+        sub: admin_id,
+        email: email.to_owned(),
+        iat: now,
+        exp: now + expire.as_secs(),
+    };
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(secret.as_bytes()),
+    )
+    .map_err(|err| err.to_string())
+}
+
+fn verify_with(secret: &str, token: &str) -> Result<Claims, String> {
+    decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(secret.as_bytes()),
+        &Validation::default(),
+    )
+    .map(|data| data.claims)
+    .map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{issue_with, verify_with};
+    use std::time::Duration;
+
+    #[test]
+    fn test_issue_and_verify() {
+        let token = issue_with("test_secret", Duration::from_secs(60), 1, "cakeal@qq.com").unwrap();
+        let claims = verify_with("test_secret", &token).unwrap();
+        assert_eq!(claims.sub, 1);
+        assert!(verify_with("test_secret", &format!("{token}x")).is_err());
+        assert!(verify_with("other_secret", &token).is_err());
+    }
+}